@@ -1,9 +1,9 @@
-use minifb::{Window, WindowOptions};
+use minifb::WindowOptions;
 
 use simple_3d::{camera::Camera, scene::Scene};
 use simple_render::color::Color;
 
-use simple_render::render::buffer::BufferSize;
+use simple_render::render::{backend::minifb::MinifbBackend, buffer::BufferSize};
 
 use simple_linear_algebra::vector::{Axis, AxisUnits, vec3::Vec3};
 
@@ -15,12 +15,12 @@ fn main() {
 
     let size = BufferSize::new(1000, 1000);
 
-    let angles = [AngleUnit::new(Axis::X, 0.5), AngleUnit::new(Axis::Y, 0.5), AngleUnit::new(Axis::Z, 0.5)];
+    // degrees per second
+    let angles = [AngleUnit::new(Axis::X, 60.0), AngleUnit::new(Axis::Y, 60.0), AngleUnit::new(Axis::Z, 60.0)];
     //let matrix = Matrix4::UNIT;
 
-    let mut option = WindowOptions::default();
-    option.resize = true;
-    let window = Window::new("Test", size.width, size.height, option).unwrap();
+    let option = WindowOptions { resize: true, ..WindowOptions::default() };
+    let backend = MinifbBackend::open("Test", size, option).unwrap();
 
     let camera = Camera::default();
     //camera.pos = Vec3::splat(0.1);
@@ -28,7 +28,8 @@ fn main() {
 
     let scene = Scene::new(vec![cube1.create(), cube2.create()], camera);
 
-    let engine = Engine::new(scene, Color::WHITE, &angles, size);
+    let mut engine = Engine::new(scene, Color::WHITE, &angles, size);
+    engine.set_screenshot_path("cube_example.png");
 
-    engine.run(120.0, window);
+    engine.run(120.0, backend);
 }
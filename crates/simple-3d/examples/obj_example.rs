@@ -0,0 +1,38 @@
+use std::{env, fs::File};
+
+use minifb::WindowOptions;
+
+use simple_3d::{camera::{Camera, controller::OrbitController}, engine::Engine, scene::Scene, shape::{AngleUnit, Shape}};
+use simple_linear_algebra::{matrix::Unit, num_traits::Zero, vector::{Axis, AxisUnits, quaternion::Quaternion, vec3::Vec3}};
+use simple_render::{color::Color, render::{backend::minifb::MinifbBackend, buffer::BufferSize}};
+
+// cargo run --example obj_example -- path/to/model.obj
+fn main() {
+    let path = env::args().nth(1).expect("pass a path to an .obj file");
+
+    let shapes = match Shape::from_obj(File::open(&path).unwrap()) {
+        Ok(shapes) => shapes,
+        Err(err) => panic!("{path}: {err}"),
+    };
+
+    let size = BufferSize::new(1000, 1000);
+
+    // degrees per second
+    let angles = [AngleUnit::new(Axis::Y, 30.0)];
+
+    let option = WindowOptions { resize: true, ..WindowOptions::default() };
+    let backend = MinifbBackend::open("Obj", size, option).unwrap();
+
+    let camera = Camera::new(Vec3::Z * -5.0, Quaternion::UNIT);
+
+    let center = shapes.iter().fold(Vec3::ZERO, |acc, shape| acc + *shape.center()) * (1.0 / shapes.len().max(1) as f64);
+
+    let scene = Scene::new(shapes, camera);
+
+    let mut engine = Engine::new(scene, Color::WHITE, &angles, size);
+
+    // arrows or WASD orbit around the model, Equal and Minus zoom
+    engine.set_controller(OrbitController::new(center, 5.0));
+
+    engine.run(60.0, backend);
+}
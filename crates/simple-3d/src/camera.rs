@@ -1,15 +1,148 @@
-use simple_linear_algebra::{matrix::{Unit, matrix4::Matrix4}, num_traits::Zero, vector::{Vector, quaternion::Quaternion, vec3::Vec3}};
+use simple_linear_algebra::{matrix::{Unit, matrix3::Matrix3, matrix4::Matrix4}, num_traits::Zero, vector::{AxisUnits, Vector, quaternion::Quaternion, vec3::Vec3, vec4::Vec4}};
 
 use crate::shape::AngleUnit;
 
+pub mod controller;
+
+// closer or thinner than this and the depth loses all precision
+const MIN_NEAR: f64 = 1e-6;
+const MIN_DEPTH: f64 = 1e-6;
+const MIN_HEIGHT: f64 = 1e-9;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection {
+    // fov is vertical, in degrees
+    Perspective { fov: f64, near: f64, far: f64 },
+    // height of the visible area in world units, the width follows the aspect ratio
+    Orthographic { height: f64, near: f64, far: f64 }
+}
+
+impl Projection {
+    pub const DEFAULT_PERSPECTIVE: Self = Projection::Perspective { fov: 90.0, near: 0.1, far: 100.0 };
+
+    pub fn near(&self) -> f64 {
+        match *self {
+            Projection::Perspective { near, .. } | Projection::Orthographic { near, .. } => near
+        }
+    }
+
+    pub fn far(&self) -> f64 {
+        match *self {
+            Projection::Perspective { far, .. } | Projection::Orthographic { far, .. } => far
+        }
+    }
+
+    // factor < 1 zooms in, the field of view is kept inside (0; 180) degrees
+    pub fn zoomed(&self, factor: f64) -> Self {
+        let zoomed = match *self {
+            Projection::Perspective { fov, near, far } => {
+                let fov = (fov * factor).clamp(1.0, 179.0);
+
+                Projection::Perspective { fov, near, far }
+            }
+
+            Projection::Orthographic { height, near, far } => {
+                Projection::Orthographic { height: height * factor, near, far }
+            }
+        };
+
+        zoomed.clamped()
+    }
+
+    // the orthographic view that matches this one at the given distance from the camera
+    pub fn to_orthographic(&self, distance: f64) -> Self {
+        match *self {
+            Projection::Perspective { fov, near, far } => {
+                let height = 2.0 * distance * (fov.to_radians() / 2.0).tan();
+
+                Projection::Orthographic { height, near, far }
+            }
+
+            orthographic => orthographic
+        }
+    }
+
+    // the perspective view that matches this one at the given distance from the camera
+    pub fn to_perspective(&self, distance: f64) -> Self {
+        match *self {
+            Projection::Orthographic { height, near, far } => {
+                let fov = (2.0 * (height / (2.0 * distance)).atan()).to_degrees();
+
+                Projection::Perspective { fov, near, far }
+            }
+
+            perspective => perspective
+        }
+    }
+
+    // The closest projection that does not divide by zero: the fov is kept inside [1; 179] degrees,
+    // the perspective near plane in front of the camera, the orthographic height positive and far beyond near
+    pub fn clamped(&self) -> Self {
+        let beyond = |near: f64, far: f64| far.max(near + MIN_DEPTH);
+
+        match *self {
+            Projection::Perspective { fov, near, far } => {
+                let near = near.max(MIN_NEAR);
+
+                Projection::Perspective { fov: fov.clamp(1.0, 179.0), near, far: beyond(near, far) }
+            }
+
+            Projection::Orthographic { height, near, far } => {
+                let near = if near.is_finite() { near } else { 0.0 };
+
+                Projection::Orthographic { height: height.abs().max(MIN_HEIGHT), near, far: beyond(near, far) }
+            }
+        }
+    }
+
+    // Maps view space into clip space where -w <= x, y, z <= w inside the frustum.
+    // The camera looks along +z, x and y are flipped to match the screen orientation
+    pub fn to_matrix(&self, aspect: f64) -> Matrix4<f64> {
+        let flip = Matrix4::row_major_new(
+            Vec4::new(-1.0, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, -1.0, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, 1.0, 0.0),
+            Vec4::new(0.0, 0.0, 0.0, 1.0)
+        );
+
+        let projection = match *self {
+            Projection::Perspective { fov, near, far } => Matrix4::persp_lh_matrix(fov, aspect, near, far),
+            Projection::Orthographic { height, near, far } => Matrix4::ortho_lh_matrix(height * aspect, height, near, far)
+        };
+
+        flip * projection
+    }
+}
+
+impl Default for Projection {
+    fn default() -> Self {
+        Self::DEFAULT_PERSPECTIVE
+    }
+}
+
 pub struct Camera {
     pub pos: Vec3<f64>,
-    quater: Quaternion<f64>
+    quater: Quaternion<f64>,
+    projection: Projection
 }
 
 impl Camera {
     pub const fn new(pos: Vec3<f64>, quater: Quaternion<f64>) -> Self {
-        Self { pos, quater }
+        Self { pos, quater, projection: Projection::DEFAULT_PERSPECTIVE }
+    }
+
+    // the projection is clamped, see `Projection::clamped`
+    pub fn with_projection(mut self, projection: Projection) -> Self {
+        self.projection = projection.clamped();
+        self
+    }
+
+    pub fn projection(&self) -> &Projection {
+        &self.projection
+    }
+
+    pub fn set_projection(&mut self, projection: Projection) {
+        self.projection = projection.clamped();
     }
 
     pub fn from_angles(pos: Vec3<f64>, angles: &[AngleUnit]) -> Self {
@@ -23,6 +156,46 @@ impl Camera {
         &self.quater
     }
 
+    pub fn set_quater(&mut self, quater: Quaternion<f64>) {
+        self.quater = quater.to_normalized();
+    }
+
+    // The camera looks along its local +z, +y is up and +x is the left side of the screen
+    pub fn forward(&self) -> Vec3<f64> {
+        Vec3::Z.to_raw_rotated(self.quater)
+    }
+
+    pub fn up(&self) -> Vec3<f64> {
+        Vec3::Y.to_raw_rotated(self.quater)
+    }
+
+    pub fn left(&self) -> Vec3<f64> {
+        Vec3::X.to_raw_rotated(self.quater)
+    }
+
+    // Turns the camera to the target keeping `up` above it. Does nothing if the target is the camera position,
+    // an `up` parallel to the view direction is replaced by any perpendicular one
+    pub fn look_at(&mut self, target: Vec3<f64>, up: Vec3<f64>) {
+        let forward = (target - self.pos).to_normalized();
+
+        if forward == Vec3::ZERO {
+            return;
+        }
+
+        let mut left = up.cross(forward);
+
+        if left.length() < 1e-9 {
+            let axis = if forward.x.abs() < 0.9 { Vec3::X } else { Vec3::Y };
+
+            left = axis.cross(forward);
+        }
+
+        let left = left.to_normalized();
+        let up = forward.cross(left);
+
+        self.quater = Quaternion::from_rotation_matrix(Matrix3::new(left, up, forward)).to_normalized();
+    }
+
     pub fn raw_rotate(&mut self, quater: Quaternion<f64>) {
         self.quater = self.quater * quater;
     }
@@ -47,3 +220,41 @@ impl Default for Camera {
         Self::new(Vec3::ZERO, Quaternion::UNIT)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::camera::Projection;
+
+    fn is_finite(projection: Projection) -> bool {
+        let matrix = projection.to_matrix(1.5);
+
+        [matrix.i, matrix.j, matrix.k, matrix.w]
+            .iter()
+            .all(|column| [column.x, column.y, column.z, column.w].iter().all(|value| value.is_finite()))
+    }
+
+    #[test]
+    fn clamped() {
+        let broken = [
+            Projection::Perspective { fov: 0.0, near: 0.1, far: 100.0 },
+            Projection::Perspective { fov: 90.0, near: 1.0, far: 1.0 },
+            Projection::Orthographic { height: 0.0, near: 0.1, far: 100.0 },
+            Projection::Orthographic { height: 2.0, near: 5.0, far: 5.0 }
+        ];
+
+        for projection in broken {
+            assert!(!is_finite(projection), "{projection:?}");
+            assert!(is_finite(projection.clamped()), "{projection:?}");
+        }
+
+        // tan(90) is finite in f64, but flattens everything into a point
+        let wide = Projection::Perspective { fov: 180.0, near: 0.1, far: 100.0 }.clamped();
+        assert_eq!(wide, Projection::Perspective { fov: 179.0, near: 0.1, far: 100.0 });
+
+        // a perspective near plane at the camera maps every depth to the far plane
+        let near = Projection::Perspective { fov: 90.0, near: 0.0, far: 100.0 }.clamped();
+        assert!(near.near() > 0.0);
+
+        assert_eq!(Projection::DEFAULT_PERSPECTIVE.clamped(), Projection::DEFAULT_PERSPECTIVE);
+    }
+}
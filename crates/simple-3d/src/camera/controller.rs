@@ -0,0 +1,401 @@
+use simple_linear_algebra::{num_traits::Zero, vector::{Axis, AxisUnits, Vector, quaternion::Quaternion, vec2::Vec2, vec3::Vec3}};
+use simple_render::render::input::Key;
+
+use crate::{camera::Camera, shape::AngleUnit};
+
+// Moves the camera in response to input, `Engine` hands every key to its controller first
+pub trait CameraController: Send + Sync {
+    // called once when plugged in, so the controller can pick up the current camera state
+    fn attach(&mut self, _camera: &mut Camera) {}
+
+    // returns true if the key was used, the camera is expected to have changed then
+    fn key_pressed(&mut self, camera: &mut Camera, key: Key) -> bool;
+
+    fn key_released(&mut self, _camera: &mut Camera, _key: Key) -> bool {
+        false
+    }
+
+    // every `AppHandler::update` step, returns true if the camera changed
+    fn update(&mut self, _camera: &mut Camera, _dt: f64) -> bool {
+        false
+    }
+
+    // cursor movement in pixels while the left button is held
+    fn mouse_dragged(&mut self, _camera: &mut Camera, _delta: Vec2<f64>) -> bool {
+        false
+    }
+
+    // positive delta scrolls up
+    fn mouse_wheel(&mut self, _camera: &mut Camera, _delta: f64) -> bool {
+        false
+    }
+}
+
+// yaw turns around the world y axis, positive pitch looks down, both in degrees
+fn orientation(yaw: f64, pitch: f64) -> Quaternion<f64> {
+    AngleUnit::unification_to_quater(&[AngleUnit(Axis::Y, yaw), AngleUnit(Axis::X, pitch)])
+        .to_normalized()
+}
+
+fn yaw_pitch(direction: Vec3<f64>) -> (f64, f64) {
+    let direction = direction.to_normalized();
+
+    let yaw = direction.x.atan2(direction.z).to_degrees();
+    let pitch = (-direction.y).clamp(-1.0, 1.0).asin().to_degrees();
+
+    (yaw, pitch)
+}
+
+// a wheel step moves as far as flying for this long
+const WHEEL_STEP_TIME: f64 = 1.0 / 60.0;
+
+// Free-fly first person camera: WASD and the wheel move along the view, Space and LeftShift along the world y axis,
+// arrows and dragging turn. The pitch is clamped so the camera never flips over. Held keys move the camera
+// in `update`, so the speed does not depend on how often the keys repeat
+pub struct FlyController {
+    speed: f64,
+    turn_speed: f64,
+    mouse_sensitivity: f64,
+    yaw: f64,
+    pitch: f64,
+    max_pitch: f64,
+    held: Vec<Key>
+}
+
+impl FlyController {
+    // speed is in world units and turn speed in degrees per second
+    pub const fn new(speed: f64, turn_speed: f64) -> Self {
+        Self { speed, turn_speed, mouse_sensitivity: 0.2, yaw: 0.0, pitch: 0.0, max_pitch: 89.0, held: Vec::new() }
+    }
+
+    // degrees per dragged pixel
+    pub const fn with_mouse_sensitivity(mut self, mouse_sensitivity: f64) -> Self {
+        self.mouse_sensitivity = mouse_sensitivity;
+        self
+    }
+
+    pub const fn with_max_pitch(mut self, max_pitch: f64) -> Self {
+        self.max_pitch = max_pitch;
+        self
+    }
+
+    pub fn yaw(&self) -> f64 {
+        self.yaw
+    }
+
+    pub fn pitch(&self) -> f64 {
+        self.pitch
+    }
+
+    fn turn(&mut self, camera: &mut Camera, yaw: f64, pitch: f64) {
+        self.yaw = (self.yaw + yaw) % 360.0;
+        self.pitch = (self.pitch + pitch).clamp(-self.max_pitch, self.max_pitch);
+
+        camera.set_quater(orientation(self.yaw, self.pitch));
+    }
+}
+
+impl Default for FlyController {
+    fn default() -> Self {
+        Self::new(6.0, 30.0)
+    }
+}
+
+impl CameraController for FlyController {
+    // the camera is left as it is, roll included, until the first turn
+    fn attach(&mut self, camera: &mut Camera) {
+        let (yaw, pitch) = yaw_pitch(camera.forward());
+
+        self.yaw = yaw;
+        self.pitch = pitch.clamp(-self.max_pitch, self.max_pitch);
+    }
+
+    fn key_pressed(&mut self, _camera: &mut Camera, key: Key) -> bool {
+        let used = matches!(
+            key,
+            Key::W | Key::S | Key::A | Key::D | Key::Space | Key::LeftShift | Key::Up | Key::Down | Key::Left | Key::Right
+        );
+
+        if used && !self.held.contains(&key) {
+            self.held.push(key);
+        }
+
+        used
+    }
+
+    fn key_released(&mut self, _camera: &mut Camera, key: Key) -> bool {
+        self.held.retain(|&held| held != key);
+
+        false
+    }
+
+    fn update(&mut self, camera: &mut Camera, dt: f64) -> bool {
+        if self.held.is_empty() {
+            return false;
+        }
+
+        let mut direction = Vec3::ZERO;
+        let (mut yaw, mut pitch) = (0.0, 0.0);
+
+        for &key in &self.held {
+            match key {
+                Key::W => direction += camera.forward(),
+                Key::S => direction -= camera.forward(),
+                Key::A => direction += camera.left(),
+                Key::D => direction -= camera.left(),
+                Key::Space => direction += Vec3::Y,
+                Key::LeftShift => direction -= Vec3::Y,
+
+                Key::Up => pitch -= 1.0,
+                Key::Down => pitch += 1.0,
+                Key::Left => yaw += 1.0,
+                Key::Right => yaw -= 1.0,
+
+                _ => ()
+            }
+        }
+
+        // diagonals are not faster, opposite keys cancel out
+        if direction.length() > 1e-9 {
+            camera.pos += direction.to_normalized() * (self.speed * dt);
+        }
+
+        if yaw != 0.0 || pitch != 0.0 {
+            self.turn(camera, yaw * self.turn_speed * dt, pitch * self.turn_speed * dt);
+        }
+
+        true
+    }
+
+    // the view follows the cursor, +x is the left side of the screen
+    fn mouse_dragged(&mut self, camera: &mut Camera, delta: Vec2<f64>) -> bool {
+        self.turn(camera, -delta.x * self.mouse_sensitivity, delta.y * self.mouse_sensitivity);
+
+        true
+    }
+
+    fn mouse_wheel(&mut self, camera: &mut Camera, delta: f64) -> bool {
+        camera.pos += camera.forward() * (self.speed * WHEEL_STEP_TIME * delta);
+
+        true
+    }
+}
+
+// Circles around a target looking at it: arrows, WASD or dragging orbit,
+// Equal, Minus and the wheel zoom by changing the distance
+pub struct OrbitController {
+    target: Vec3<f64>,
+    distance: f64,
+    min_distance: f64,
+    max_distance: f64,
+    turn_speed: f64,
+    mouse_sensitivity: f64,
+    zoom_factor: f64,
+    yaw: f64,
+    pitch: f64,
+    max_pitch: f64
+}
+
+impl OrbitController {
+    pub const fn new(target: Vec3<f64>, distance: f64) -> Self {
+        Self {
+            target,
+            distance,
+            min_distance: 0.1,
+            max_distance: f64::INFINITY,
+            turn_speed: 1.0,
+            mouse_sensitivity: 0.3,
+            zoom_factor: 0.95,
+            yaw: 0.0,
+            pitch: 0.0,
+            max_pitch: 89.0
+        }
+    }
+
+    pub const fn with_distance_limits(mut self, min: f64, max: f64) -> Self {
+        self.min_distance = min;
+        self.max_distance = max;
+        self
+    }
+
+    // turn speed in degrees per key press
+    pub const fn with_turn_speed(mut self, turn_speed: f64) -> Self {
+        self.turn_speed = turn_speed;
+        self
+    }
+
+    // degrees per dragged pixel
+    pub const fn with_mouse_sensitivity(mut self, mouse_sensitivity: f64) -> Self {
+        self.mouse_sensitivity = mouse_sensitivity;
+        self
+    }
+
+    // factor < 1 applied to the distance when zooming in
+    pub const fn with_zoom_factor(mut self, zoom_factor: f64) -> Self {
+        self.zoom_factor = zoom_factor;
+        self
+    }
+
+    pub const fn with_max_pitch(mut self, max_pitch: f64) -> Self {
+        self.max_pitch = max_pitch;
+        self
+    }
+
+    pub fn target(&self) -> Vec3<f64> {
+        self.target
+    }
+
+    pub fn set_target(&mut self, camera: &mut Camera, target: Vec3<f64>) {
+        self.target = target;
+        self.place(camera);
+    }
+
+    pub fn distance(&self) -> f64 {
+        self.distance
+    }
+
+    pub fn zoom(&mut self, camera: &mut Camera, factor: f64) {
+        self.distance *= factor;
+        self.place(camera);
+    }
+
+    pub fn orbit(&mut self, camera: &mut Camera, yaw: f64, pitch: f64) {
+        self.yaw = (self.yaw + yaw) % 360.0;
+        self.pitch += pitch;
+        self.place(camera);
+    }
+
+    fn place(&mut self, camera: &mut Camera) {
+        self.distance = self.distance.clamp(self.min_distance, self.max_distance);
+        self.pitch = self.pitch.clamp(-self.max_pitch, self.max_pitch);
+
+        let quater = orientation(self.yaw, self.pitch);
+
+        camera.pos = self.target - Vec3::Z.to_raw_rotated(quater) * self.distance;
+        camera.set_quater(quater);
+    }
+}
+
+impl CameraController for OrbitController {
+    // keeps the direction the target is seen from, unless the camera is right at it
+    fn attach(&mut self, camera: &mut Camera) {
+        let offset = self.target - camera.pos;
+
+        if offset != Vec3::ZERO {
+            (self.yaw, self.pitch) = yaw_pitch(offset);
+        }
+
+        self.place(camera);
+    }
+
+    fn key_pressed(&mut self, camera: &mut Camera, key: Key) -> bool {
+        match key {
+            Key::Left | Key::A => self.orbit(camera, self.turn_speed, 0.0),
+            Key::Right | Key::D => self.orbit(camera, -self.turn_speed, 0.0),
+            Key::Up | Key::W => self.orbit(camera, 0.0, self.turn_speed),
+            Key::Down | Key::S => self.orbit(camera, 0.0, -self.turn_speed),
+
+            Key::Equal => self.zoom(camera, self.zoom_factor),
+            Key::Minus => self.zoom(camera, 1.0 / self.zoom_factor),
+
+            _ => return false
+        }
+
+        true
+    }
+
+    // dragging moves the camera the same way as the arrows
+    fn mouse_dragged(&mut self, camera: &mut Camera, delta: Vec2<f64>) -> bool {
+        self.orbit(camera, -delta.x * self.mouse_sensitivity, -delta.y * self.mouse_sensitivity);
+
+        true
+    }
+
+    fn mouse_wheel(&mut self, camera: &mut Camera, delta: f64) -> bool {
+        self.zoom(camera, self.zoom_factor.powf(delta));
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simple_linear_algebra::{matrix::Unit, num_traits::Zero, vector::{Vector, quaternion::Quaternion, vec3::Vec3}};
+    use simple_render::render::input::Key;
+
+    use crate::camera::{Camera, controller::{CameraController, FlyController}};
+
+    #[test]
+    fn fly_attach_keeps_orientation() {
+        let mut camera = Camera::new(Vec3::new(1.0, 2.0, 3.0), Quaternion::UNIT);
+        camera.look_at(Vec3::new(4.0, 0.0, 5.0), Vec3::new(1.0, 1.0, 0.0));
+
+        let quater = *camera.quater();
+        let forward = camera.forward();
+
+        let mut controller = FlyController::default();
+        controller.attach(&mut camera);
+
+        assert_eq!(*camera.quater(), quater);
+
+        // the first turn starts from the same view direction
+        controller.key_pressed(&mut camera, Key::Left);
+        controller.update(&mut camera, 1.0 / 60.0);
+
+        assert!((camera.forward() - forward).length() < 0.05);
+    }
+
+    #[test]
+    fn fly_speed_per_second() {
+        // the same second of flying forward in big or small steps, however often the key repeats
+        let flown = |steps: usize, presses_per_step: usize| {
+            let mut camera = Camera::new(Vec3::ZERO, Quaternion::UNIT);
+            let mut controller = FlyController::new(2.0, 90.0);
+
+            for _ in 0..steps {
+                for _ in 0..presses_per_step {
+                    controller.key_pressed(&mut camera, Key::W);
+                }
+
+                controller.update(&mut camera, 1.0 / steps as f64);
+            }
+
+            camera.pos
+        };
+
+        for pos in [flown(60, 1), flown(4, 1), flown(60, 5)] {
+            assert!((pos - Vec3::new(0.0, 0.0, 2.0)).length() < 1e-9, "{pos:?}");
+        }
+    }
+
+    #[test]
+    fn fly_held_keys() {
+        let mut camera = Camera::new(Vec3::ZERO, Quaternion::UNIT);
+        let mut controller = FlyController::new(2.0, 90.0);
+
+        assert!(!controller.update(&mut camera, 1.0));
+        assert!(!controller.key_pressed(&mut camera, Key::P));
+
+        // a diagonal is as fast as a straight line
+        controller.key_pressed(&mut camera, Key::W);
+        controller.key_pressed(&mut camera, Key::Space);
+        assert!(controller.update(&mut camera, 0.5));
+
+        assert!((camera.pos.length() - 1.0).abs() < 1e-9, "{:?}", camera.pos);
+        assert!((camera.pos.y - camera.pos.z).abs() < 1e-9, "{:?}", camera.pos);
+
+        // nothing moves once the keys are up
+        controller.key_released(&mut camera, Key::W);
+        controller.key_released(&mut camera, Key::Space);
+
+        let pos = camera.pos;
+        assert!(!controller.update(&mut camera, 1.0));
+        assert_eq!(camera.pos, pos);
+
+        // turning is in degrees per second too
+        controller.key_pressed(&mut camera, Key::Left);
+        controller.update(&mut camera, 0.5);
+
+        assert!((controller.yaw() - 45.0).abs() < 1e-9, "{}", controller.yaw());
+    }
+}
@@ -1,19 +1,39 @@
-use std::{ops::Mul, sync::{Arc, Mutex}, thread};
+use std::{ops::Mul, path::PathBuf, sync::{Arc, Mutex}};
 
-use minifb::{Key, Window};
-use simple_linear_algebra::{num_traits::Zero, vector::{Axis, Vector, quaternion::Quaternion, vec2::Vec2, vec3::Vec3}};
-use simple_render::{color::Color, render::{Render, app_handler::{AppHandler, Event}, buffer::BufferSize, wait}};
+use simple_linear_algebra::vector::{Vector, vec2::Vec2, vec3::Vec3};
+use simple_render::{color::Color, render::{Render, app_handler::{AppHandler, Event}, backend::Backend, buffer::{Buffer, BufferSize}, headless::Headless, input::{Key, MouseButton}}};
 
-use crate::{engine::render_cache::RenderCache, scene::Scene, shape::AngleUnit};
+use crate::{camera::{Projection, controller::{CameraController, FlyController}}, engine::{clipping::{clip_line, clip_polygon}, depth_buffer::DepthBuffer, render_cache::RenderCache}, scene::Scene, shape::{AngleUnit, FaceUnit, Shape}};
 
 pub mod render_cache;
+pub mod depth_buffer;
+pub mod clipping;
+
+// distance at which perspective and orthographic views match when toggling
+const PROJECTION_FOCUS: f64 = 5.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenderMode {
+    Wireframe,
+    // shapes without faces are still drawn as wireframe
+    #[default]
+    Solid
+}
 
 pub struct Engine {
     scene: Scene,
     color: Color,
-    quater: Quaternion<f64>,
+    // rotation of every shape around its center, degrees per second
+    angles: Vec<AngleUnit>,
     render_cache: RenderCache,
-    need_to_redraw: bool
+    depth_buffer: DepthBuffer,
+    render_mode: RenderMode,
+    controller: Box<dyn CameraController>,
+    // cursor position while dragging with the left button
+    drag: Option<Vec2<f64>>,
+    need_to_redraw: bool,
+    screenshot_path: Option<PathBuf>,
+    take_screenshot: bool
 }
 
 impl Engine {
@@ -23,42 +43,85 @@ impl Engine {
         angles: &[AngleUnit],
         size: BufferSize
     ) -> Self {
-        let quater = AngleUnit::unification_to_quater(angles).to_normalized();
+        let mut scene = scene;
 
         let render_cache = RenderCache::init(scene.shapes(), size, &scene.camera);
 
+        let depth_buffer = DepthBuffer::init(size);
+
+        let mut controller = FlyController::default();
+        controller.attach(&mut scene.camera);
+
         let need_to_redraw = true;
 
-        Self { scene, color, quater, render_cache, need_to_redraw }
+        Self {
+            scene,
+            color,
+            angles: angles.to_vec(),
+            render_cache,
+            depth_buffer,
+            render_mode: RenderMode::default(),
+            controller: Box::new(controller),
+            drag: None,
+            need_to_redraw,
+            screenshot_path: None,
+            take_screenshot: false
+        }
     }
 
-    pub fn event_loop(&mut self) {
-        self.scene.raw_rotate_shapes(self.quater);
+    pub fn render_mode(&self) -> RenderMode {
+        self.render_mode
+    }
 
+    pub fn set_render_mode(&mut self, mode: RenderMode) {
+        self.render_mode = mode;
         self.need_to_redraw = true;
     }
 
-    pub fn run(self, fps: f64, window: Window) {
-        let clone = Arc::new(Mutex::new(self));
-        let clone2 = clone.clone();
+    // replaces the default `FlyController`
+    pub fn set_controller<C: CameraController + 'static>(&mut self, controller: C) {
+        let mut controller = Box::new(controller);
+        controller.attach(&mut self.scene.camera);
 
-        let mut render = Render::new(clone, fps, window);
+        self.controller = controller;
+        self.need_to_redraw = true;
+    }
+
+    // F12 saves the shown frame to this path
+    pub fn set_screenshot_path<P: Into<PathBuf>>(&mut self, path: P) {
+        self.screenshot_path = Some(path.into());
+    }
 
-        let tick = 1.0 / fps;
+    pub fn run<B: Backend>(self, fps: f64, backend: B) {
+        let clone = Arc::new(Mutex::new(self));
 
-        thread::spawn(move || {
-            loop {
-                clone2.lock().unwrap()
-                    .event_loop();
+        let mut render = Render::new(clone, fps, backend);
 
-                wait(tick);
-            }
-        });
+        // F3 shows the frame rate and timings
+        render.set_overlay_key(Key::F3);
 
         render.run();
     }
 
+    // every frame is one update step of 1 / 60 seconds
+    pub fn run_headless(self, size: BufferSize, frames: usize, keys: &[(usize, Key)]) -> Buffer {
+        let app = Arc::new(Mutex::new(self));
+
+        let mut headless = Headless::new(app, size, frames)
+            .with_keys(keys);
+
+        headless.run();
+
+        headless.into_buffer()
+    }
+
     pub fn to_real(&self, vec: Vec2<f64>, size: BufferSize) -> Vec2<isize> {
+        let Vec2 { x, y } = self.to_screen(vec, size);
+
+        Vec2::new(x as isize, y as isize)
+    }
+
+    pub fn to_screen(&self, vec: Vec2<f64>, size: BufferSize) -> Vec2<f64> {
         //[-1; 1] + 1 -> [0; 2]
         // [0; 2] / 2 -> [0; 1]
         // [0; 1] * width -> [0; width]
@@ -69,10 +132,26 @@ impl Engine {
         // [1; 0] * height -> [height; 0]
         let y =  (vec.y + 1.0) / 2.0 * size.height as f64;
 
-        Vec2::new(x as isize, y as isize)
+        Vec2::new(x, y)
     }
 }
 
+// flat shading by how directly the face looks at the camera
+fn shade(color: Color, camera_pos: Vec3<f64>, shape: &Shape, face: &FaceUnit) -> Color {
+    let vertexes = shape.vertexes();
+    let (a, b, c) = (vertexes[face.0], vertexes[face.1], vertexes[face.2]);
+
+    let normal = (b - a).cross(c - a).to_normalized();
+
+    let view = ((a + b + c) * (1.0 / 3.0) - camera_pos).to_normalized();
+
+    let facing = normal.dot(view).abs();
+
+    let level = (64.0 + 191.0 * facing) as u8;
+
+    color * Color::from_rgb(level, level, level)
+}
+
 impl AppHandler for Engine {
     fn event(&mut self, event: Event) {
         match event {
@@ -88,94 +167,168 @@ impl AppHandler for Engine {
 
                         // offset camera matrix mul vertex
                         // rotate vertex by camera quater
-                        // project vertex into 3d
                         // perspective matrix mul vertex
-                        // project vertex into 2d
-                        // to real coordinates
-                        let vertex3 = self.render_cache.persp_matrix().mul(
+                        let clip = self.render_cache.persp_matrix().mul(
                             self.render_cache.camera_disp_matrix()
                                 .mul(vertex4)
                                 .set_w(0.0)
                                 .to_rotated(self.render_cache.camera_quater())
-                        )
-                            .into_vec3();
-
-                        let vertex2 = self.to_real(
-                            vertex3
-                                .to_projected()
-                                .into_vec2(),
-                            buffer.size
+                                .set_w(1.0)
                         );
 
-                        self.render_cache.push(index, vertex2);
+                        self.render_cache.push(index, clip);
                     }
                 }
 
-                buffer.fill(Color::new(0));
+                buffer.fill(Color::BLACK);
+
+                self.depth_buffer.reset(buffer.size);
+
+                let camera_pos = self.scene.camera.pos;
 
                 for (index, shape) in self.scene.shapes().iter().enumerate() {
-                    for edge in shape.edges() {
-                        let start = self.render_cache.get(index, edge.0);
+                    if self.render_mode == RenderMode::Wireframe || shape.faces().is_empty() {
+                        for edge in shape.edges() {
+                            let start = self.render_cache.get(index, edge.0);
+
+                            let end = self.render_cache.get(index, edge.1);
+
+                            let Some((start, end)) = clip_line(start, end) else {
+                                continue;
+                            };
+
+                            buffer.draw_line_aa(
+                                self.to_screen(start.to_projected().into_vec2(), buffer.size),
+                                self.to_screen(end.to_projected().into_vec2(), buffer.size),
+                                self.color
+                            );
+                        }
 
-                        let end = self.render_cache.get(index, edge.1);
+                        continue;
+                    }
+
+                    for face in shape.faces() {
+                        let polygon = clip_polygon(&[
+                            self.render_cache.get(index, face.0),
+                            self.render_cache.get(index, face.1),
+                            self.render_cache.get(index, face.2)
+                        ]);
+
+                        if polygon.len() < 3 {
+                            continue;
+                        }
+
+                        // project into 2d, the normalized z is kept as the depth
+                        let screen: Vec<Vec3<f64>> = polygon
+                            .iter()
+                            .map(|vertex| {
+                                let ndc = vertex.to_projected();
+
+                                self.to_screen(ndc.into_vec2(), buffer.size).extend_to_vec3(ndc.z)
+                            })
+                            .collect();
+
+                        let color = shade(self.color, camera_pos, shape, face);
 
-                        //TODO: replace isize with usize in draw_line
-                        buffer.draw_line(start, end, self.color);
+                        // the clipped polygon is convex, so a fan covers it
+                        for i in 1..screen.len() - 1 {
+                            let triangle = Vec3::new(screen[0], screen[i], screen[i + 1]);
+
+                            self.depth_buffer.fill_triangle(buffer, triangle, color);
+                        }
                     }
                 }
 
                 self.render_cache.clear();
             },
 
-            Event::KeyPressed { key } => {
-                self.need_to_redraw = true;
+            Event::KeyPressed { key, repeat } => {
+                if self.controller.key_pressed(&mut self.scene.camera, key) {
+                    self.need_to_redraw = true;
+                    return;
+                }
+
                 match key {
-                    Key::W => {
-                        self.scene.camera.pos += Vec3::ZERO.set_z(0.1).to_raw_rotated(self.render_cache.camera_quater());
-                    }
+                    Key::P if !repeat => {
+                        let projection = *self.scene.camera.projection();
 
-                    Key::S => {
-                        self.scene.camera.pos -= Vec3::ZERO.set_z(0.1).to_raw_rotated(self.render_cache.camera_quater());
-                    }
+                        let toggled = match projection {
+                            Projection::Perspective { .. } => projection.to_orthographic(PROJECTION_FOCUS),
+                            Projection::Orthographic { .. } => projection.to_perspective(PROJECTION_FOCUS)
+                        };
 
-                    Key::A => {
-                        self.scene.camera.pos += Vec3::ZERO.set_x(0.1).to_raw_rotated(self.render_cache.camera_quater())
+                        self.scene.camera.set_projection(toggled);
                     }
 
-                    Key::D => {
-                        self.scene.camera.pos -= Vec3::ZERO.set_x(0.1).to_raw_rotated(self.render_cache.camera_quater());
-                    }
+                    Key::Equal => {
+                        let projection = self.scene.camera.projection().zoomed(0.95);
 
-                    Key::Space => {
-                        self.scene.camera.pos += Vec3::ZERO.set_y(0.1).to_raw_rotated(self.render_cache.camera_quater());
+                        self.scene.camera.set_projection(projection);
                     }
 
-                    Key::LeftShift => {
-                        self.scene.camera.pos -= Vec3::ZERO.set_y(0.1).to_raw_rotated(self.render_cache.camera_quater());
-                    }
+                    Key::Minus => {
+                        let projection = self.scene.camera.projection().zoomed(1.05);
 
-                    Key::Up => {
-                        self.scene.camera.rotate(&[AngleUnit(Axis::X, -0.5)]);
+                        self.scene.camera.set_projection(projection);
                     }
 
-                    Key::Down => {
-                        self.scene.camera.rotate(&[AngleUnit(Axis::X, 0.5)]);
+                    Key::F12 if !repeat => {
+                        self.take_screenshot = true;
                     }
 
-                    Key::Left => {
-                        self.scene.camera.rotate(&[AngleUnit(Axis::Y, 0.5)]);
-                    }
+                    _ => return
+                }
 
-                    Key::Right => {
-                        self.scene.camera.rotate(&[AngleUnit(Axis::Y, -0.5)]);
-                    }
+                self.need_to_redraw = true;
+            }
 
-                    _ => (),
-                }
+            Event::KeyReleased { key } => {
+                self.need_to_redraw |= self.controller.key_released(&mut self.scene.camera, key);
             }
+
+            Event::MouseButtonPressed { button: MouseButton::Left, pos } => {
+                self.drag = pos;
+            }
+
+            Event::MouseButtonReleased { button: MouseButton::Left, .. } => {
+                self.drag = None;
+            }
+
+            Event::MouseMoved { pos } => {
+                let Some(last) = self.drag.replace(pos) else {
+                    return;
+                };
+
+                self.need_to_redraw |= self.controller.mouse_dragged(&mut self.scene.camera, pos - last);
+            }
+
+            Event::MouseWheel { delta } => {
+                self.need_to_redraw |= self.controller.mouse_wheel(&mut self.scene.camera, delta.y);
+            }
+
+            _ => ()
         }
     }
 
+    fn update(&mut self, dt: f64) {
+        self.need_to_redraw |= self.controller.update(&mut self.scene.camera, dt);
+
+        if self.angles.iter().all(|angle| angle.1 == 0.0) {
+            return;
+        }
+
+        let angles: Vec<AngleUnit> = self.angles
+            .iter()
+            .map(|angle| AngleUnit(angle.0, angle.1 * dt))
+            .collect();
+
+        let quater = AngleUnit::unification_to_quater(&angles).to_normalized();
+
+        self.scene.raw_rotate_shapes(quater);
+
+        self.need_to_redraw = true;
+    }
+
     fn need_to_redraw(&self) -> bool {
         self.need_to_redraw
     }
@@ -183,4 +336,46 @@ impl AppHandler for Engine {
     fn redrawed(&mut self) {
         self.need_to_redraw = false;
     }
+
+    fn screenshot(&mut self) -> Option<PathBuf> {
+        if !std::mem::take(&mut self.take_screenshot) {
+            return None;
+        }
+
+        self.screenshot_path.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simple_linear_algebra::vector::{vec2::Vec2, vec3::Vec3};
+    use simple_render::{color::Color, render::{buffer::{Buffer, BufferSize}, input::Key}};
+
+    use crate::{camera::Camera, engine::Engine, scene::Scene, shape::cube::Cube};
+
+    fn lit_pixels(buffer: &Buffer) -> usize {
+        buffer.raw_buffer.0.iter().filter(|&&pixel| pixel != Color::BLACK.0).count()
+    }
+
+    #[test]
+    fn headless_cube() {
+        let size = BufferSize::new(64, 64);
+
+        let engine = |keys: &[(usize, Key)]| {
+            let scene = Scene::new(vec![Cube::new(Vec3::new(0.0, 0.0, 3.0), 1.0).create()], Camera::default());
+
+            Engine::new(scene, Color::WHITE, &[], size).run_headless(size, 10, keys)
+        };
+
+        let still = engine(&[]);
+
+        assert_ne!(still.get_point(Vec2::new(32, 32)), Color::BLACK.0);
+        assert_eq!(still.get_point(Vec2::new(2, 2)), Color::BLACK.0);
+
+        // flying forward brings the cube closer
+        let keys = (0..10).map(|frame| (frame, Key::W)).collect::<Vec<_>>();
+        let closer = engine(&keys);
+
+        assert!(lit_pixels(&closer) > lit_pixels(&still) * 2, "{} {}", lit_pixels(&closer), lit_pixels(&still));
+    }
 }
@@ -0,0 +1,168 @@
+use simple_linear_algebra::vector::vec4::Vec4;
+
+// Frustum planes of the clip space, a point is inside when -w <= x, y, z <= w
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClipPlane {
+    Near,
+    Far,
+    Left,
+    Right,
+    Top,
+    Bottom
+}
+
+impl ClipPlane {
+    // the near plane goes first so nothing behind the camera reaches the others
+    pub const ALL: [ClipPlane; 6] = [
+        ClipPlane::Near,
+        ClipPlane::Far,
+        ClipPlane::Left,
+        ClipPlane::Right,
+        ClipPlane::Top,
+        ClipPlane::Bottom
+    ];
+
+    // signed distance, negative means outside
+    pub fn distance(&self, vertex: Vec4<f64>) -> f64 {
+        match self {
+            ClipPlane::Near => vertex.w + vertex.z,
+            ClipPlane::Far => vertex.w - vertex.z,
+            ClipPlane::Left => vertex.w + vertex.x,
+            ClipPlane::Right => vertex.w - vertex.x,
+            ClipPlane::Top => vertex.w + vertex.y,
+            ClipPlane::Bottom => vertex.w - vertex.y
+        }
+    }
+}
+
+fn lerp(start: Vec4<f64>, end: Vec4<f64>, t: f64) -> Vec4<f64> {
+    start + (end - start) * t
+}
+
+pub fn clip_line(start: Vec4<f64>, end: Vec4<f64>) -> Option<(Vec4<f64>, Vec4<f64>)> {
+    let (mut t0, mut t1) = (0.0, 1.0);
+
+    for plane in ClipPlane::ALL {
+        let (d0, d1) = (plane.distance(start), plane.distance(end));
+
+        if d0 < 0.0 && d1 < 0.0 {
+            return None;
+        }
+
+        if d0 < 0.0 {
+            t0 = f64::max(t0, d0 / (d0 - d1));
+        } else if d1 < 0.0 {
+            t1 = f64::min(t1, d0 / (d0 - d1));
+        }
+    }
+
+    if t0 > t1 {
+        return None;
+    }
+
+    Some((lerp(start, end, t0), lerp(start, end, t1)))
+}
+
+// Sutherland-Hodgman, the result is a convex polygon with up to 9 vertices
+pub fn clip_polygon(polygon: &[Vec4<f64>]) -> Vec<Vec4<f64>> {
+    let mut output = polygon.to_vec();
+
+    for plane in ClipPlane::ALL {
+        if output.is_empty() {
+            break;
+        }
+
+        let input = std::mem::take(&mut output);
+
+        for (index, &current) in input.iter().enumerate() {
+            let previous = input[(index + input.len() - 1) % input.len()];
+
+            let (d_prev, d_cur) = (plane.distance(previous), plane.distance(current));
+
+            if d_cur >= 0.0 {
+                if d_prev < 0.0 {
+                    output.push(lerp(previous, current, d_prev / (d_prev - d_cur)));
+                }
+
+                output.push(current);
+            } else if d_prev >= 0.0 {
+                output.push(lerp(previous, current, d_prev / (d_prev - d_cur)));
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use simple_linear_algebra::vector::vec4::Vec4;
+
+    use crate::engine::clipping::{ClipPlane, clip_line, clip_polygon};
+
+    fn close(a: Vec4<f64>, b: Vec4<f64>) -> bool {
+        [a.x - b.x, a.y - b.y, a.z - b.z, a.w - b.w].iter().all(|d| d.abs() < 1e-12)
+    }
+
+    #[test]
+    fn line_inside_and_outside() {
+        let (start, end) = (Vec4::new(-0.5, 0.2, 0.0, 1.0), Vec4::new(0.5, -0.2, 0.5, 1.0));
+
+        assert_eq!(clip_line(start, end), Some((start, end)));
+
+        // both ends right of the frustum, and both behind the camera
+        assert_eq!(clip_line(Vec4::new(2.0, 0.0, 0.0, 1.0), Vec4::new(3.0, 0.5, 0.0, 1.0)), None);
+        assert_eq!(clip_line(Vec4::new(0.0, 0.0, -1.0, 0.0), Vec4::new(0.0, 0.0, -2.0, -1.0)), None);
+
+        // each end outside a different plane, the line misses the corner between them
+        assert_eq!(clip_line(Vec4::new(0.0, 2.5, 0.0, 1.0), Vec4::new(2.5, 0.0, 0.0, 1.0)), None);
+    }
+
+    #[test]
+    fn line_through_near_plane() {
+        // the end is at the camera, where w is 0 and it can't be divided by
+        let (start, end) = (Vec4::new(0.0, 0.0, 0.5, 1.0), Vec4::new(0.0, 0.0, -2.0, 0.0));
+
+        let (clipped_start, clipped_end) = clip_line(start, end).unwrap();
+
+        assert_eq!(clipped_start, start);
+        assert!(ClipPlane::Near.distance(clipped_end).abs() < 1e-12);
+        assert!(clipped_end.w > 0.5, "{clipped_end:?}");
+
+        // the clipped end is still on the line
+        let t = (clipped_end.w - start.w) / (end.w - start.w);
+        assert!(close(clipped_end, start + (end - start) * t));
+    }
+
+    #[test]
+    fn polygon_inside_and_outside() {
+        let triangle = [Vec4::new(0.0, 0.0, 0.0, 1.0), Vec4::new(0.5, 0.0, 0.2, 1.0), Vec4::new(0.0, 0.5, 0.4, 1.0)];
+
+        assert_eq!(clip_polygon(&triangle), triangle);
+
+        let left = triangle.map(|vertex| Vec4::new(vertex.x - 2.0, vertex.y, vertex.z, vertex.w));
+        assert!(clip_polygon(&left).is_empty());
+
+        let behind = triangle.map(|vertex| Vec4::new(vertex.x, vertex.y, -1.0, 0.0));
+        assert!(clip_polygon(&behind).is_empty());
+    }
+
+    #[test]
+    fn triangle_into_quad() {
+        // the second vertex is past the right plane, the coordinates are interpolated along the cut edges
+        let (a, b, c) = (Vec4::new(0.0, 0.0, 0.0, 1.0), Vec4::new(2.0, 0.0, 0.8, 1.0), Vec4::new(0.0, 1.0, 0.4, 1.0));
+
+        let clipped = clip_polygon(&[a, b, c]);
+
+        let expected = [a, Vec4::new(1.0, 0.0, 0.4, 1.0), Vec4::new(1.0, 0.5, 0.6, 1.0), c];
+
+        assert_eq!(clipped.len(), 4);
+        assert!(clipped.iter().zip(expected).all(|(&vertex, expected)| close(vertex, expected)), "{clipped:?}");
+
+        // a vertex behind the camera is cut off at the near plane, not projected through w = 0
+        let near = clip_polygon(&[Vec4::new(0.0, 0.0, 0.5, 1.0), Vec4::new(0.5, 0.0, 0.5, 1.0), Vec4::new(0.0, 0.0, -1.0, 0.0)]);
+
+        assert_eq!(near.len(), 4);
+        assert!(near.iter().all(|vertex| vertex.w > 0.0 && ClipPlane::Near.distance(*vertex) > -1e-12), "{near:?}");
+    }
+}
@@ -0,0 +1,169 @@
+use simple_linear_algebra::vector::{vec2::Vec2, vec3::Vec3};
+use simple_render::{color::Color, render::buffer::{Buffer, BufferSize}};
+
+// Depth per pixel, smaller is closer. Values must be linear in screen space
+pub struct DepthBuffer {
+    depths: Vec<f64>,
+    size: BufferSize
+}
+
+impl DepthBuffer {
+    pub fn init(size: BufferSize) -> Self {
+        let depths = vec![f64::INFINITY; size.width * size.height];
+
+        Self { depths, size }
+    }
+
+    pub fn size(&self) -> BufferSize {
+        self.size
+    }
+
+    pub fn clear(&mut self) {
+        self.depths.fill(f64::INFINITY);
+    }
+
+    pub fn reset(&mut self, size: BufferSize) {
+        if self.size != size {
+            *self = Self::init(size);
+        } else {
+            self.clear();
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> f64 {
+        self.depths[y * self.size.width + x]
+    }
+
+    // stores the depth and returns true if it is closer than the current one
+    pub fn test_and_set(&mut self, x: usize, y: usize, depth: f64) -> bool {
+        let current = &mut self.depths[y * self.size.width + x];
+
+        if depth < *current {
+            *current = depth;
+            true
+        } else {
+            false
+        }
+    }
+
+    // triangle vertices are (screen x, screen y, depth)
+    pub fn fill_triangle(&mut self, buffer: &mut Buffer, triangle: Vec3<Vec3<f64>>, color: Color) {
+        let Vec3 { x: a, y: b, z: c } = triangle;
+
+        let area = edge_func(a, b, c);
+
+        if area == 0.0 {
+            return;
+        }
+
+        let max_x = (buffer.size.width.min(self.size.width) as f64 - 1.0).max(0.0);
+        let max_y = (buffer.size.height.min(self.size.height) as f64 - 1.0).max(0.0);
+
+        let min_x = a.x.min(b.x).min(c.x).floor().max(0.0);
+        let max_x = a.x.max(b.x).max(c.x).ceil().min(max_x);
+
+        let min_y = a.y.min(b.y).min(c.y).floor().max(0.0);
+        let max_y = a.y.max(b.y).max(c.y).ceil().min(max_y);
+
+        if min_x > max_x || min_y > max_y {
+            return;
+        }
+
+        for y in min_y as usize..=max_y as usize {
+            for x in min_x as usize..=max_x as usize {
+                // sample at the pixel center
+                let point = Vec3::new(x as f64 + 0.5, y as f64 + 0.5, 0.0);
+
+                // dividing by the signed area makes both windings positive inside
+                let w0 = edge_func(b, c, point) / area;
+                let w1 = edge_func(c, a, point) / area;
+                let w2 = edge_func(a, b, point) / area;
+
+                if w0 < 0.0 || w1 < 0.0 || w2 < 0.0 {
+                    continue;
+                }
+
+                let depth = w0 * a.z + w1 * b.z + w2 * c.z;
+
+                if self.test_and_set(x, y, depth) {
+                    buffer.raw_draw_point(Vec2::new(x as isize, y as isize), color);
+                }
+            }
+        }
+    }
+}
+
+fn edge_func(a: Vec3<f64>, b: Vec3<f64>, point: Vec3<f64>) -> f64 {
+    (b.x - a.x) * (point.y - a.y) - (b.y - a.y) * (point.x - a.x)
+}
+
+#[cfg(test)]
+mod tests {
+    use simple_linear_algebra::vector::{vec2::Vec2, vec3::Vec3};
+    use simple_render::{color::Color, render::buffer::{Buffer, BufferSize}};
+
+    use crate::engine::depth_buffer::DepthBuffer;
+
+    #[test]
+    fn closer_wins() {
+        let mut depth = DepthBuffer::init(BufferSize::new(2, 2));
+
+        assert_eq!(depth.get(1, 0), f64::INFINITY);
+
+        assert!(depth.test_and_set(1, 0, 5.0));
+        assert!(depth.test_and_set(1, 0, 2.0));
+        assert!(!depth.test_and_set(1, 0, 3.0));
+        assert_eq!(depth.get(1, 0), 2.0);
+
+        // a tie keeps what was drawn first
+        assert!(!depth.test_and_set(1, 0, 2.0));
+
+        // the other pixels are untouched
+        assert!(depth.test_and_set(0, 1, 100.0));
+        assert_eq!(depth.get(0, 0), f64::INFINITY);
+    }
+
+    #[test]
+    fn clear_and_reset() {
+        let mut depth = DepthBuffer::init(BufferSize::new(2, 2));
+
+        depth.test_and_set(1, 1, 1.0);
+        depth.clear();
+
+        assert_eq!(depth.get(1, 1), f64::INFINITY);
+
+        depth.test_and_set(1, 1, 1.0);
+        depth.reset(BufferSize::new(2, 2));
+
+        assert_eq!(depth.get(1, 1), f64::INFINITY);
+
+        depth.reset(BufferSize::new(3, 1));
+
+        assert!(depth.size() == BufferSize::new(3, 1));
+        assert!(depth.test_and_set(2, 0, 1.0));
+        assert_eq!(depth.get(2, 0), 1.0);
+    }
+
+    #[test]
+    fn overlapping_triangles() {
+        let size = BufferSize::new(8, 8);
+
+        let triangle = |z: f64| Vec3::new(Vec3::new(0.0, 0.0, z), Vec3::new(8.0, 0.0, z), Vec3::new(0.0, 8.0, z));
+        // a far red and a close blue triangle, in both orders
+        let drawn = |first: (f64, Color), second: (f64, Color)| {
+            let mut buffer = Buffer::init(size);
+            let mut depth = DepthBuffer::init(size);
+
+            buffer.fill(Color::BLACK);
+            depth.fill_triangle(&mut buffer, triangle(first.0), first.1);
+            depth.fill_triangle(&mut buffer, triangle(second.0), second.1);
+
+            buffer
+        };
+
+        for buffer in [drawn((2.0, Color::RED), (1.0, Color::BLUE)), drawn((1.0, Color::BLUE), (2.0, Color::RED))] {
+            assert_eq!(buffer.get_point(Vec2::new(1, 1)), Color::BLUE.0);
+            assert_eq!(buffer.get_point(Vec2::new(7, 7)), Color::BLACK.0);
+        }
+    }
+}
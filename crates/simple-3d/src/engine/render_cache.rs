@@ -1,16 +1,16 @@
-use simple_linear_algebra::{matrix::matrix4::Matrix4, vector::{quaternion::Quaternion, vec2::Vec2, vec3::Vec3}};
+use simple_linear_algebra::{matrix::matrix4::Matrix4, vector::{quaternion::Quaternion, vec3::Vec3, vec4::Vec4}};
 use simple_render::render::buffer::BufferSize;
 
-use crate::{camera::Camera, shape::Shape};
+use crate::{camera::{Camera, Projection}, shape::Shape};
 
 pub struct RenderCache {
-    pool: Vec<Vec<Vec2<isize>>>,
-    persp_matrix: (Matrix4<f64>, BufferSize),
+    pool: Vec<Vec<Vec4<f64>>>,
+    persp_matrix: (Matrix4<f64>, BufferSize, Projection),
     camera: (Matrix4<f64>, Quaternion<f64>, Vec3<f64>),
 }
 
 impl RenderCache {
-    fn new(pool: Vec<Vec<Vec2<isize>>>, persp_matrix: (Matrix4<f64>, BufferSize), camera: (Matrix4<f64>, Quaternion<f64>, Vec3<f64>)) -> Self {
+    fn new(pool: Vec<Vec<Vec4<f64>>>, persp_matrix: (Matrix4<f64>, BufferSize, Projection), camera: (Matrix4<f64>, Quaternion<f64>, Vec3<f64>)) -> Self {
         RenderCache { pool, persp_matrix, camera }
     }
 
@@ -23,18 +23,21 @@ impl RenderCache {
             pool.push(vec);
         }
 
-        let persp_matrix = (Matrix4::persp_rh_matrix(90.0, size.width as f64 / size.height as f64, 0.1, 100.0), size);
+        let projection = *camera.projection();
+
+        let persp_matrix = (projection.to_matrix(size.width as f64 / size.height as f64), size, projection);
 
         let camera = (camera.to_displacement_matrix(), camera.to_rotation_quaternion(), camera.pos);
 
         Self::new(pool, persp_matrix, camera)
     }
 
-    pub fn push(&mut self, index: usize, value: Vec2<isize>) {
+    // clip space position of a vertex
+    pub fn push(&mut self, index: usize, value: Vec4<f64>) {
         self.pool[index].push(value);
     }
 
-    pub fn get(&self, index1: usize, index2: usize) -> Vec2<isize> {
+    pub fn get(&self, index1: usize, index2: usize) -> Vec4<f64> {
         self.pool[index1][index2]
     }
 
@@ -45,8 +48,8 @@ impl RenderCache {
     }
 
     pub fn reload(&mut self, size: BufferSize, camera: &Camera) {
-        if self.persp_matrix.1 != size {
-            self.reload_persp_matrix(size);
+        if self.persp_matrix.1 != size || self.persp_matrix.2 != *camera.projection() {
+            self.reload_persp_matrix(size, *camera.projection());
         }
 
         if self.camera.2 != camera.pos {
@@ -66,10 +69,14 @@ impl RenderCache {
         self.persp_matrix.1
     }
 
-    pub fn reload_persp_matrix(&mut self, new_size: BufferSize) {
-        let matrix = Matrix4::persp_rh_matrix(90.0, new_size.width as f64 / new_size.height as f64, 0.1, 100.0);
+    pub fn persp_matrix_projection(&self) -> Projection {
+        self.persp_matrix.2
+    }
+
+    pub fn reload_persp_matrix(&mut self, new_size: BufferSize, projection: Projection) {
+        let matrix = projection.to_matrix(new_size.width as f64 / new_size.height as f64);
 
-        self.persp_matrix = (matrix, new_size);
+        self.persp_matrix = (matrix, new_size, projection);
     }
 
     pub fn camera_disp_matrix(&self) -> Matrix4<f64> {
@@ -86,6 +93,7 @@ impl RenderCache {
 
     pub fn reload_camera_disp_matrix(&mut self, camera: &Camera) {
         self.camera.0 = camera.to_displacement_matrix();
+        self.camera.2 = camera.pos;
     }
 
     pub fn reload_camera_quater(&mut self, camera: &Camera) {
@@ -96,3 +104,4 @@ impl RenderCache {
         self.camera.2
     }
 }
+
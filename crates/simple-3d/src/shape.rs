@@ -1,12 +1,17 @@
 use std::ops::Mul;
 
-use simple_linear_algebra::{matrix::{Unit, matrix4::Matrix4}, vector::{Axis, Vector, quaternion::Quaternion, vec3::Vec3}};
+use simple_linear_algebra::{matrix::{Unit, matrix4::Matrix4}, vector::{Axis, Vector, quaternion::Quaternion, vec2::Vec2, vec3::Vec3}};
 
 pub mod cube;
+pub mod obj;
 
 #[derive(Clone)]
 pub struct EdgeUnit(pub usize, pub usize);
 
+// vertex indices of a triangle, counter-clockwise when seen from outside
+#[derive(Clone)]
+pub struct FaceUnit(pub usize, pub usize, pub usize);
+
 #[derive(Clone, Copy)]
 pub struct AngleUnit(pub Axis, pub f64);
 
@@ -36,6 +41,10 @@ impl AngleUnit {
 pub struct Shape {
     vertexes: Vec<Vec3<f64>>,
     edges: Vec<EdgeUnit>,
+    faces: Vec<FaceUnit>,
+    // either empty or one per vertex
+    normals: Vec<Vec3<f64>>,
+    tex_coords: Vec<Vec2<f64>>,
     center: Vec3<f64>
 }
 
@@ -43,15 +52,38 @@ impl Shape {
     pub const fn new(
         vertexes: Vec<Vec3<f64>>,
         edges:  Vec<EdgeUnit>,
+        faces: Vec<FaceUnit>,
         center: Vec3<f64>
     ) -> Self {
-        Self { vertexes, edges, center }
+        Self { vertexes, edges, faces, normals: Vec::new(), tex_coords: Vec::new(), center }
+    }
+
+    pub fn with_normals(mut self, normals: Vec<Vec3<f64>>) -> Self {
+        self.normals = normals;
+        self
+    }
+
+    pub fn with_tex_coords(mut self, tex_coords: Vec<Vec2<f64>>) -> Self {
+        self.tex_coords = tex_coords;
+        self
     }
 
     pub fn edges(&self) -> &[EdgeUnit] {
         &self.edges
     }
 
+    pub fn faces(&self) -> &[FaceUnit] {
+        &self.faces
+    }
+
+    pub fn normals(&self) -> &[Vec3<f64>] {
+        &self.normals
+    }
+
+    pub fn tex_coords(&self) -> &[Vec2<f64>] {
+        &self.tex_coords
+    }
+
     pub fn vertexes(&self) -> &[Vec3<f64>] {
         &self.vertexes
     }
@@ -68,6 +100,10 @@ impl Shape {
         for i in &mut self.vertexes {
             *i = (*i - self.center).to_raw_rotated(quater) + self.center;
         }
+
+        for normal in &mut self.normals {
+            normal.raw_rotate(quater);
+        }
     }
 
     pub fn rotate(&mut self, angles: &[AngleUnit]) {
@@ -83,7 +119,7 @@ impl Shape {
         let mut vec = self.vertexes.clone();
 
         for i in &mut vec {
-            *i = *i + self.center;
+            *i += self.center;
         }
 
         vec
@@ -1,6 +1,6 @@
 use simple_linear_algebra::vector::vec3::Vec3;
 
-use crate::shape::{EdgeUnit, Shape};
+use crate::shape::{EdgeUnit, FaceUnit, Shape};
 
 #[derive(Clone)]
 pub struct Cube {
@@ -37,6 +37,15 @@ impl Cube {
             EdgeUnit(4, 6), EdgeUnit(5, 7), EdgeUnit(6, 7)
         ];
 
-        Shape::new(vertexes, edges, self.center)
+        let faces: Vec<FaceUnit> = vec![
+            FaceUnit(0, 1, 3), FaceUnit(0, 3, 2), // -x
+            FaceUnit(4, 6, 7), FaceUnit(4, 7, 5), // +x
+            FaceUnit(0, 4, 5), FaceUnit(0, 5, 1), // -y
+            FaceUnit(2, 3, 7), FaceUnit(2, 7, 6), // +y
+            FaceUnit(0, 2, 6), FaceUnit(0, 6, 4), // -z
+            FaceUnit(1, 5, 7), FaceUnit(1, 7, 3)  // +z
+        ];
+
+        Shape::new(vertexes, edges, faces, self.center)
     }
 }
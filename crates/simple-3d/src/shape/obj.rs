@@ -0,0 +1,365 @@
+use std::{collections::{HashMap, HashSet}, error::Error, fmt::{self, Display}, fs::File, io::{self, BufRead, BufReader}, num::ParseFloatError, str::SplitWhitespace};
+
+use simple_linear_algebra::{num_traits::Zero, vector::{vec2::Vec2, vec3::Vec3}};
+
+use crate::shape::{EdgeUnit, FaceUnit, Shape};
+
+#[derive(Debug)]
+pub enum ObjErrorKind {
+    Io(io::Error),
+    InvalidNumber(ParseFloatError),
+    InvalidIndex(String),
+    // the index is 1-based in the file, negative ones are already resolved
+    IndexOutOfRange(isize),
+    MissingValue(&'static str),
+    TooFewVertices
+}
+
+#[derive(Debug)]
+pub struct ObjError {
+    pub line: usize,
+    pub kind: ObjErrorKind
+}
+
+impl ObjError {
+    pub const fn new(line: usize, kind: ObjErrorKind) -> Self {
+        Self { line, kind }
+    }
+}
+
+impl Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+
+        match &self.kind {
+            ObjErrorKind::Io(err) => write!(f, "{err}"),
+            ObjErrorKind::InvalidNumber(err) => write!(f, "invalid number: {err}"),
+            ObjErrorKind::InvalidIndex(index) => write!(f, "invalid index `{index}`"),
+            ObjErrorKind::IndexOutOfRange(index) => write!(f, "index {index} is out of range"),
+            ObjErrorKind::MissingValue(value) => write!(f, "missing {value}"),
+            ObjErrorKind::TooFewVertices => write!(f, "an element needs at least two vertices, a face three")
+        }
+    }
+}
+
+impl Error for ObjError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            ObjErrorKind::Io(err) => Some(err),
+            ObjErrorKind::InvalidNumber(err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+// position, texture coordinate and normal indices of one face corner
+type Corner = (usize, Option<usize>, Option<usize>);
+
+// a group of faces and lines that becomes one shape
+#[derive(Default)]
+struct Group {
+    corners: Vec<Corner>,
+    indices: HashMap<Corner, usize>,
+    edges: Vec<EdgeUnit>,
+    edge_keys: HashSet<(usize, usize)>,
+    faces: Vec<FaceUnit>
+}
+
+impl Group {
+    fn is_empty(&self) -> bool {
+        self.faces.is_empty() && self.edges.is_empty()
+    }
+
+    fn vertex(&mut self, corner: Corner) -> usize {
+        *self.indices.entry(corner).or_insert_with(|| {
+            self.corners.push(corner);
+            self.corners.len() - 1
+        })
+    }
+
+    // edges are deduplicated by position, corners may differ in normals only
+    fn edge(&mut self, start: usize, end: usize) {
+        let (a, b) = (self.corners[start].0, self.corners[end].0);
+        let key = (a.min(b), a.max(b));
+
+        if a != b && self.edge_keys.insert(key) {
+            self.edges.push(EdgeUnit(start, end));
+        }
+    }
+
+    fn into_shape(self, positions: &[Vec3<f64>], tex_coords: &[Vec2<f64>], normals: &[Vec3<f64>]) -> Shape {
+        let vertexes: Vec<Vec3<f64>> = self.corners
+            .iter()
+            .map(|corner| positions[corner.0])
+            .collect();
+
+        let center = if vertexes.is_empty() {
+            Vec3::ZERO
+        } else {
+            vertexes.iter().fold(Vec3::ZERO, |acc, &v| acc + v) * (1.0 / vertexes.len() as f64)
+        };
+
+        let mut shape = Shape::new(vertexes, self.edges, self.faces, center);
+
+        if self.corners.iter().any(|corner| corner.1.is_some()) {
+            shape = shape.with_tex_coords(
+                self.corners
+                    .iter()
+                    .map(|corner| corner.1.map_or(Vec2::ZERO, |i| tex_coords[i]))
+                    .collect()
+            );
+        }
+
+        if self.corners.iter().any(|corner| corner.2.is_some()) {
+            shape = shape.with_normals(
+                self.corners
+                    .iter()
+                    .map(|corner| corner.2.map_or(Vec3::ZERO, |i| normals[i]))
+                    .collect()
+            );
+        }
+
+        shape
+    }
+}
+
+fn parse_float(values: &mut SplitWhitespace, name: &'static str) -> Result<f64, ObjErrorKind> {
+    values.next()
+        .ok_or(ObjErrorKind::MissingValue(name))?
+        .parse()
+        .map_err(ObjErrorKind::InvalidNumber)
+}
+
+// resolves a 1-based or negative (relative to the end) index
+fn parse_index(index: &str, len: usize) -> Result<usize, ObjErrorKind> {
+    let value: isize = index.parse()
+        .map_err(|_| ObjErrorKind::InvalidIndex(index.to_string()))?;
+
+    let resolved = match value {
+        0 => return Err(ObjErrorKind::InvalidIndex(index.to_string())),
+        1.. => value - 1,
+        _ => len as isize + value
+    };
+
+    if resolved < 0 || resolved >= len as isize {
+        return Err(ObjErrorKind::IndexOutOfRange(value));
+    }
+
+    Ok(resolved as usize)
+}
+
+// `v`, `v/vt`, `v//vn` or `v/vt/vn`
+fn parse_corner(corner: &str, positions: usize, tex_coords: usize, normals: usize) -> Result<Corner, ObjErrorKind> {
+    let mut parts = corner.split('/');
+
+    let position = parse_index(parts.next().unwrap_or(""), positions)?;
+
+    let tex_coord = match parts.next() {
+        None | Some("") => None,
+        Some(index) => Some(parse_index(index, tex_coords)?)
+    };
+
+    let normal = match parts.next() {
+        None | Some("") => None,
+        Some(index) => Some(parse_index(index, normals)?)
+    };
+
+    Ok((position, tex_coord, normal))
+}
+
+impl Shape {
+    pub fn from_obj(file: File) -> Result<Vec<Shape>, ObjError> {
+        Self::parse_obj(BufReader::new(file))
+    }
+
+    // Every `g` or `o` statement starts a new shape, polygons are split into triangle fans.
+    // Unsupported statements such as materials are skipped
+    pub fn parse_obj<R: BufRead>(reader: R) -> Result<Vec<Shape>, ObjError> {
+        let mut positions = Vec::new();
+        let mut tex_coords = Vec::new();
+        let mut normals = Vec::new();
+
+        let mut groups = vec![Group::default()];
+
+        for (index, line) in reader.lines().enumerate() {
+            let number = index + 1;
+            let error = |kind| ObjError::new(number, kind);
+
+            let line = line.map_err(|err| error(ObjErrorKind::Io(err)))?;
+
+            let line = match line.find('#') {
+                Some(comment) => &line[..comment],
+                None => &line
+            };
+
+            let mut values = line.split_whitespace();
+
+            let Some(statement) = values.next() else {
+                continue;
+            };
+
+            match statement {
+                "v" => {
+                    let x = parse_float(&mut values, "x coordinate").map_err(error)?;
+                    let y = parse_float(&mut values, "y coordinate").map_err(error)?;
+                    let z = parse_float(&mut values, "z coordinate").map_err(error)?;
+
+                    positions.push(Vec3::new(x, y, z));
+                }
+
+                "vt" => {
+                    let u = parse_float(&mut values, "u coordinate").map_err(error)?;
+                    let v = match values.next() {
+                        Some(v) => v.parse().map_err(|err| error(ObjErrorKind::InvalidNumber(err)))?,
+                        None => 0.0
+                    };
+
+                    tex_coords.push(Vec2::new(u, v));
+                }
+
+                "vn" => {
+                    let x = parse_float(&mut values, "x coordinate").map_err(error)?;
+                    let y = parse_float(&mut values, "y coordinate").map_err(error)?;
+                    let z = parse_float(&mut values, "z coordinate").map_err(error)?;
+
+                    normals.push(Vec3::new(x, y, z));
+                }
+
+                "f" | "l" => {
+                    let group = groups.last_mut().unwrap();
+
+                    let mut polygon = Vec::new();
+
+                    for corner in values {
+                        let corner = parse_corner(corner, positions.len(), tex_coords.len(), normals.len())
+                            .map_err(error)?;
+
+                        polygon.push(group.vertex(corner));
+                    }
+
+                    let is_face = statement == "f";
+
+                    if polygon.len() < 2 || (is_face && polygon.len() < 3) {
+                        return Err(error(ObjErrorKind::TooFewVertices));
+                    }
+
+                    for pair in polygon.windows(2) {
+                        group.edge(pair[0], pair[1]);
+                    }
+
+                    if is_face {
+                        group.edge(polygon[polygon.len() - 1], polygon[0]);
+
+                        for i in 1..polygon.len() - 1 {
+                            group.faces.push(FaceUnit(polygon[0], polygon[i], polygon[i + 1]));
+                        }
+                    }
+                }
+
+                "g" | "o" if !groups.last().unwrap().is_empty() => {
+                    groups.push(Group::default());
+                }
+
+                _ => ()
+            }
+        }
+
+        let shapes = groups
+            .into_iter()
+            .filter(|group| !group.is_empty())
+            .map(|group| group.into_shape(&positions, &tex_coords, &normals))
+            .collect();
+
+        Ok(shapes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simple_linear_algebra::vector::{vec2::Vec2, vec3::Vec3};
+
+    use crate::shape::{Shape, obj::{ObjError, ObjErrorKind}};
+
+    const TRIANGLE: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\n";
+
+    fn parse(obj: &str) -> Result<Vec<Shape>, ObjError> {
+        Shape::parse_obj(obj.as_bytes())
+    }
+
+    fn faces(shape: &Shape) -> Vec<(usize, usize, usize)> {
+        shape.faces().iter().map(|face| (face.0, face.1, face.2)).collect()
+    }
+
+    #[test]
+    fn negative_indices() {
+        // relative to the vertices read so far, not to the whole file
+        let shapes = parse(&format!("{TRIANGLE}f -3 -2 -1\nv 0 0 1\nf 1 2 -1\n")).unwrap();
+
+        assert_eq!(shapes.len(), 1);
+        assert_eq!(shapes[0].vertexes(), &[Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0)]);
+        assert_eq!(faces(&shapes[0]), vec![(0, 1, 2), (0, 1, 3)]);
+    }
+
+    #[test]
+    fn corner_forms() {
+        let shape = &parse(&format!("{TRIANGLE}vt 0 0\nvt 1\nvt 0 1\nvn 0 0 1\nf 1/1/1 2/2/1 3/3/1\n")).unwrap()[0];
+
+        assert_eq!(shape.tex_coords(), &[Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(0.0, 1.0)]);
+        assert_eq!(shape.normals(), &[Vec3::new(0.0, 0.0, 1.0); 3]);
+
+        let shape = &parse(&format!("{TRIANGLE}vt 0.5 0.5\nf 1/1 2/1 3/1\n")).unwrap()[0];
+
+        assert_eq!(shape.tex_coords(), &[Vec2::new(0.5, 0.5); 3]);
+        assert!(shape.normals().is_empty());
+
+        // the same position with another normal is another vertex, but not another edge
+        let shape = &parse(&format!("{TRIANGLE}vn 0 0 1\nvn 0 0 -1\nf 1//1 2//1 3//1\nf 1//2 3//2 2//2\n")).unwrap()[0];
+
+        assert!(shape.tex_coords().is_empty());
+        assert_eq!(shape.vertexes().len(), 6);
+        assert_eq!(shape.edges().len(), 3);
+        assert_eq!(faces(shape), vec![(0, 1, 2), (3, 4, 5)]);
+    }
+
+    #[test]
+    fn polygons() {
+        let shapes = parse(&format!("o quad\n{TRIANGLE}v 1 1 0\nf 1 2 4 3\ng lines\nl 1 2 3\n")).unwrap();
+
+        assert_eq!(shapes.len(), 2);
+
+        // split into a fan around the first corner
+        assert_eq!(faces(&shapes[0]), vec![(0, 1, 2), (0, 2, 3)]);
+        assert_eq!(shapes[0].edges().len(), 4);
+
+        assert!(shapes[1].faces().is_empty());
+        assert_eq!(shapes[1].edges().len(), 2);
+    }
+
+    #[test]
+    fn error_lines() {
+        let error = |obj: &str| parse(obj).err().unwrap();
+
+        let err = error("v 0 0 0\n\n# comment\nv 1 x 0\n");
+        assert!(err.line == 4 && matches!(err.kind, ObjErrorKind::InvalidNumber(_)), "{err}");
+
+        let err = error("v 1 2 # z is missing\n");
+        assert!(err.line == 1 && matches!(err.kind, ObjErrorKind::MissingValue("z coordinate")), "{err}");
+
+        let err = error(&format!("{TRIANGLE}f 1 2 5\n"));
+        assert!(err.line == 4 && matches!(err.kind, ObjErrorKind::IndexOutOfRange(5)), "{err}");
+
+        let err = error(&format!("{TRIANGLE}\nf -4 -2 -1\n"));
+        assert!(err.line == 5 && matches!(err.kind, ObjErrorKind::IndexOutOfRange(-4)), "{err}");
+
+        let err = error(&format!("{TRIANGLE}f 0 1 2\n"));
+        assert!(err.line == 4 && matches!(&err.kind, ObjErrorKind::InvalidIndex(index) if index == "0"), "{err}");
+
+        let err = error(&format!("{TRIANGLE}f 1/1 2/1 3/1\n"));
+        assert!(err.line == 4 && matches!(err.kind, ObjErrorKind::IndexOutOfRange(1)), "{err}");
+
+        let err = error(&format!("{TRIANGLE}l 1 2\nf 1 2\n"));
+        assert!(err.line == 5 && matches!(err.kind, ObjErrorKind::TooFewVertices), "{err}");
+
+        assert_eq!(err.to_string(), "line 5: an element needs at least two vertices, a face three");
+    }
+}
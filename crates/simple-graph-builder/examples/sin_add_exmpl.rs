@@ -1,25 +1,31 @@
-use simple_graph_builder::Builder;
-use minifb::{Window, WindowOptions};
-use simple_render::{color::Color, render::buffer::BufferSize};
+use std::f64::consts::PI;
+
+use simple_graph_builder::{Builder, series::Series, viewport::Viewport};
+use minifb::WindowOptions;
+use simple_render::{color::Color, render::{backend::minifb::MinifbBackend, buffer::BufferSize, stroke::LineStyle}};
 
 fn main() {
     let size = BufferSize::new(1000, 1000);
 
-    let func = |x| func1(x) + func2(x);
+    let dashed = LineStyle::new(1.0).with_dash(&[8.0, 6.0], 0.0);
+
+    let mut app = Builder::new();
+    app.set_viewport(Viewport::from_ranges((-2.0 * PI, 2.0 * PI), (-2.0, 2.0)));
 
-    let app = Builder::new(func, Color::from_rgb(255, 255, 255));
+    app.add_series(Series::function(func1, Color::from_rgb(0x40, 0xA0, 0xFF)).with_name("sin(x)").with_line_style(dashed.clone()));
+    app.add_series(Series::function(func2, Color::from_rgb(0xFF, 0x80, 0x40)).with_name("0.75 sin(2x)").with_line_style(dashed));
+    app.add_series(Series::function(|x| func1(x) + func2(x), Color::WHITE).with_name("sum").with_line_style(LineStyle::new(2.0)));
 
-    let mut options = WindowOptions::default();
-    options.resize = true;
-    let window = Window::new("sin test", size.width, size.height, options).unwrap();
+    let options = WindowOptions { resize: true, ..WindowOptions::default() };
+    let backend = MinifbBackend::open("sin test", size, options).unwrap();
 
-    app.run(60.0, window);
+    app.run(60.0, backend);
 }
 
-fn func1(x: isize) -> isize {
-    ((x as f64).to_radians().sin() * 400.0) as isize
+fn func1(x: f64) -> f64 {
+    x.sin()
 }
 
-fn func2(x: isize) -> isize {
-    ((x as f64 * 2.0).to_radians().sin() * 300.0) as isize
+fn func2(x: f64) -> f64 {
+    (x * 2.0).sin() * 0.75
 }
@@ -1,16 +1,18 @@
-use simple_graph_builder::Builder;
-use minifb::{Window, WindowOptions};
-use simple_render::{color::Color, render::buffer::BufferSize};
+use std::f64::consts::PI;
+
+use simple_graph_builder::{Builder, viewport::Viewport};
+use minifb::WindowOptions;
+use simple_render::{color::Color, render::{backend::minifb::MinifbBackend, buffer::BufferSize}};
 
 fn main() {
     let size = BufferSize::new(1000, 1000);
 
-    let func = |x| ((x as f64).to_radians().sin() * 400.0) as isize;
+    let mut app = Builder::with_function(f64::sin, Color::from_rgb(255, 255, 255));
+    app.set_viewport(Viewport::from_ranges((-2.0 * PI, 2.0 * PI), (-1.5, 1.5)));
+    app.set_screenshot_path("sin_example.png");
 
-    let app = Builder::new(func, Color::from_rgb(255, 255, 255));
-    let mut options = WindowOptions::default();
-    options.resize = true;
-    let window = Window::new("sin test", size.width, size.height, options).unwrap();
+    let options = WindowOptions { resize: true, ..WindowOptions::default() };
+    let backend = MinifbBackend::open("sin test", size, options).unwrap();
 
-    app.run(10.0, window);
+    app.run(10.0, backend);
 }
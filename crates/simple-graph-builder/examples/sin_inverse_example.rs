@@ -0,0 +1,18 @@
+use simple_graph_builder::{Builder, series::Series, viewport::Viewport};
+use minifb::WindowOptions;
+use simple_render::{color::Color, render::{backend::minifb::MinifbBackend, buffer::BufferSize}};
+
+fn main() {
+    let size = BufferSize::new(1000, 1000);
+
+    let mut app = Builder::new();
+    app.set_viewport(Viewport::from_ranges((-0.5, 0.5), (-1.5, 1.5)));
+
+    // oscillates faster and faster towards 0, the sampling goes deeper there
+    app.add_series(Series::function(|x: f64| (1.0 / x).sin(), Color::from_rgb(255, 255, 255)).with_name("sin(1/x)").with_max_depth(16));
+
+    let options = WindowOptions { resize: true, ..WindowOptions::default() };
+    let backend = MinifbBackend::open("sin(1/x) test", size, options).unwrap();
+
+    app.run(60.0, backend);
+}
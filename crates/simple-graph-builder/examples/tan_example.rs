@@ -1,16 +1,17 @@
-//!failed
+use std::f64::consts::PI;
 
-use simple_graph_builder::Builder;
-use minifb::{Window, WindowOptions};
-use simple_render::{color::Color, render::buffer::BufferSize};
+use simple_graph_builder::{Builder, series::Series, viewport::Viewport};
+use minifb::WindowOptions;
+use simple_render::{color::Color, render::{backend::minifb::MinifbBackend, buffer::BufferSize}};
 
 fn main() {
     let size = BufferSize::new(1000, 1000);
-    let func = |x| (x as f64).to_radians().tan() as isize;
-    let app = Builder::new(func, Color::from_rgb(255, 255, 255));
 
-    let mut options = WindowOptions::default();
-    options.resize = true;
-    let window = Window::new("sin test", size.width, size.height, options).unwrap();
-    app.run(0.01, window);
+    let mut app = Builder::new();
+    app.set_viewport(Viewport::from_ranges((-2.0 * PI, 2.0 * PI), (-5.0, 5.0)));
+    app.add_series(Series::function(f64::tan, Color::from_rgb(255, 255, 255)).with_asymptotes(true));
+
+    let options = WindowOptions { resize: true, ..WindowOptions::default() };
+    let backend = MinifbBackend::open("tan test", size, options).unwrap();
+    app.run(60.0, backend);
 }
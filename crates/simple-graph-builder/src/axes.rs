@@ -0,0 +1,246 @@
+use simple_linear_algebra::vector::vec2::Vec2;
+use simple_render::{color::Color, render::{buffer::Buffer, font::BitmapFont}};
+
+use crate::viewport::Viewport;
+
+const TICK_LENGTH: f64 = 4.0;
+// between a tick and its label
+const LABEL_GAP: f64 = 4.0;
+// more ticks than this are not drawn, the viewport is broken then
+const MAX_TICKS: usize = 1000;
+
+// How the axes, the grid and the tick labels look. The axes cross at the origin and stick to the edge
+// of the buffer when it is out of view
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Axes {
+    axis_color: Color,
+    grid_color: Color,
+    label_color: Color,
+    grid: bool,
+    labels: bool,
+    // the smallest distance between two ticks in pixels
+    tick_spacing: f64
+}
+
+impl Axes {
+    pub const fn new() -> Self {
+        Self {
+            axis_color: Color::from_rgb(0xC0, 0xC0, 0xC0),
+            grid_color: Color::from_rgb(0x30, 0x30, 0x30),
+            label_color: Color::from_rgb(0xC0, 0xC0, 0xC0),
+            grid: true,
+            labels: true,
+            tick_spacing: 80.0
+        }
+    }
+
+    pub const fn with_colors(mut self, axis: Color, grid: Color, label: Color) -> Self {
+        self.axis_color = axis;
+        self.grid_color = grid;
+        self.label_color = label;
+        self
+    }
+
+    pub const fn with_grid(mut self, grid: bool) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    pub const fn with_labels(mut self, labels: bool) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    pub const fn with_tick_spacing(mut self, tick_spacing: f64) -> Self {
+        self.tick_spacing = tick_spacing;
+        self
+    }
+
+    pub(crate) fn draw(&self, buffer: &mut Buffer, viewport: &Viewport) {
+        let size = buffer.size;
+
+        if size.width == 0 || size.height == 0 {
+            return;
+        }
+
+        let (width, height) = (size.width as f64, size.height as f64);
+
+        let x_step = nice_step(viewport.width() * self.tick_spacing / width);
+        let y_step = nice_step(viewport.height() * self.tick_spacing / height);
+
+        let x_ticks = ticks(viewport.min.x, viewport.max.x, x_step);
+        let y_ticks = ticks(viewport.min.y, viewport.max.y, y_step);
+
+        // lines go through pixel centers so they stay one pixel wide
+        let column = |x: f64| viewport.to_screen(Vec2::new(x, 0.0), size).x.floor().clamp(0.0, width - 1.0) + 0.5;
+        let row = |y: f64| viewport.to_screen(Vec2::new(0.0, y), size).y.floor().clamp(0.0, height - 1.0) + 0.5;
+
+        if self.grid {
+            for &x in &x_ticks {
+                buffer.draw_line_aa(Vec2::new(column(x), 0.0), Vec2::new(column(x), height), self.grid_color);
+            }
+
+            for &y in &y_ticks {
+                buffer.draw_line_aa(Vec2::new(0.0, row(y)), Vec2::new(width, row(y)), self.grid_color);
+            }
+        }
+
+        let (axis_x, axis_y) = (column(0.0), row(0.0));
+
+        buffer.draw_line_aa(Vec2::new(axis_x, 0.0), Vec2::new(axis_x, height), self.axis_color);
+        buffer.draw_line_aa(Vec2::new(0.0, axis_y), Vec2::new(width, axis_y), self.axis_color);
+
+        let font = BitmapFont::builtin();
+        let text_height = font.line_height() as f64;
+
+        // labels go under the x axis and right of the y axis, flipped when that is outside the buffer.
+        // Ticks point away from the labels, so a tick is never taken for a minus sign
+        let labels_below = axis_y + LABEL_GAP + text_height <= height;
+        let labels_right = axis_x + LABEL_GAP + y_label_width(font, &y_ticks, y_step) <= width;
+
+        let tick_y = if labels_below { -TICK_LENGTH } else { TICK_LENGTH };
+        let tick_x = if labels_right { -TICK_LENGTH } else { TICK_LENGTH };
+
+        for &x in &x_ticks {
+            let start = Vec2::new(column(x), axis_y);
+            buffer.draw_line_aa(start, start + Vec2::new(0.0, tick_y), self.axis_color);
+        }
+
+        for &y in &y_ticks {
+            let start = Vec2::new(axis_x, row(y));
+            buffer.draw_line_aa(start, start + Vec2::new(tick_x, 0.0), self.axis_color);
+        }
+
+        if !self.labels {
+            return;
+        }
+
+        for &x in x_ticks.iter().filter(|&&x| x != 0.0) {
+            let text = format_tick(x, x_step);
+            let text_size = font.measure(&text, 1);
+
+            let y = match labels_below {
+                true => axis_y + LABEL_GAP,
+                false => axis_y - LABEL_GAP - text_size.height as f64
+            };
+
+            let pos = Vec2::new(column(x) as isize - text_size.width as isize / 2, y as isize);
+
+            buffer.draw_text(&text, pos, self.label_color, 1);
+        }
+
+        for &y in y_ticks.iter().filter(|&&y| y != 0.0) {
+            let text = format_tick(y, y_step);
+            let text_size = font.measure(&text, 1);
+
+            let x = match labels_right {
+                true => axis_x + LABEL_GAP,
+                false => axis_x - LABEL_GAP - text_size.width as f64
+            };
+
+            let pos = Vec2::new(x as isize, row(y) as isize - text_size.height as isize / 2);
+
+            buffer.draw_text(&text, pos, self.label_color, 1);
+        }
+
+        // the origin is labelled once, in its bottom right corner
+        if x_ticks.contains(&0.0) && y_ticks.contains(&0.0) {
+            let pos = Vec2::new((axis_x + LABEL_GAP) as isize, (axis_y + LABEL_GAP) as isize);
+
+            buffer.draw_text("0", pos, self.label_color, 1);
+        }
+    }
+}
+
+impl Default for Axes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// the widest label of the y axis
+fn y_label_width(font: &BitmapFont, ticks: &[f64], step: f64) -> f64 {
+    ticks.iter()
+        .map(|&tick| font.measure(&format_tick(tick, step), 1).width)
+        .max()
+        .unwrap_or(0) as f64
+}
+
+// The smallest 1, 2 or 5 times a power of ten that is at least `min_step`
+pub fn nice_step(min_step: f64) -> f64 {
+    if !min_step.is_finite() || min_step <= 0.0 {
+        return f64::NAN;
+    }
+
+    let magnitude = 10f64.powf(min_step.log10().floor());
+
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|&step| step >= min_step)
+        .unwrap_or(10.0 * magnitude)
+}
+
+// multiples of the step inside [min, max], empty if there would be too many
+pub fn ticks(min: f64, max: f64, step: f64) -> Vec<f64> {
+    if !step.is_finite() || step <= 0.0 || !(min.is_finite() && max.is_finite()) || (max - min) / step > MAX_TICKS as f64 {
+        return Vec::new();
+    }
+
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+
+    // built from the index so the error does not add up, + 0.0 turns -0 into 0
+    (first..=last).map(|index| index as f64 * step + 0.0).collect()
+}
+
+// as many decimals as the step needs
+pub fn format_tick(value: f64, step: f64) -> String {
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+
+    format!("{:.*}", decimals, value + 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::axes::{MAX_TICKS, format_tick, nice_step, ticks};
+
+    #[test]
+    fn nice_steps() {
+        assert_eq!(nice_step(1.0), 1.0);
+        assert_eq!(nice_step(3.0), 5.0);
+        assert_eq!(nice_step(7.0), 10.0);
+        assert_eq!(nice_step(120.0), 200.0);
+        assert!((nice_step(0.011) - 0.02).abs() < 1e-15);
+
+        for broken in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(nice_step(broken).is_nan());
+        }
+    }
+
+    #[test]
+    fn tick_values() {
+        assert_eq!(ticks(-1.2, 1.2, 0.5), vec![-1.0, -0.5, 0.0, 0.5, 1.0]);
+        assert_eq!(ticks(0.1, 0.9, 1.0), Vec::<f64>::new());
+
+        // zero is never negative, or its label would read -0
+        let zero = ticks(-1.0, -0.0, 1.0)[1];
+        assert!(zero == 0.0 && zero.is_sign_positive());
+
+        assert_eq!(ticks(0.0, MAX_TICKS as f64, 1.0).len(), MAX_TICKS + 1);
+        assert!(ticks(0.0, MAX_TICKS as f64 + 1.0, 1.0).is_empty());
+        assert!(ticks(0.0, 1.0, f64::NAN).is_empty());
+        assert!(ticks(f64::NEG_INFINITY, 1.0, 1.0).is_empty());
+    }
+
+    #[test]
+    fn tick_labels() {
+        assert_eq!(format_tick(-0.0, 1.0), "0");
+        assert_eq!(format_tick(-0.0, 0.1), "0.0");
+        assert_eq!(format_tick(200.0, 50.0), "200");
+        assert_eq!(format_tick(0.5, 0.5), "0.5");
+        assert_eq!(format_tick(-0.3, 0.1), "-0.3");
+        assert_eq!(format_tick(0.25, 0.05), "0.25");
+        assert_eq!(format_tick(0.002, 0.002), "0.002");
+    }
+}
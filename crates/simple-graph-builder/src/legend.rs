@@ -0,0 +1,182 @@
+use simple_linear_algebra::vector::vec2::Vec2;
+use simple_render::{color::Color, render::{buffer::Buffer, font::BitmapFont, stroke::LineStyle}};
+
+use crate::series::Series;
+
+// between the box and the edge of the buffer
+const MARGIN: f64 = 10.0;
+// between the box and its contents
+const PADDING: f64 = 6.0;
+// the length of the line sample before each name
+const SAMPLE_LENGTH: f64 = 24.0;
+// between the line sample and the name
+const SAMPLE_GAP: f64 = 6.0;
+const CORNER_RADIUS: f64 = 4.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LegendPosition {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight
+}
+
+// A box in a corner of the buffer with a line sample and the name of every named series
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Legend {
+    position: LegendPosition,
+    background: Color,
+    border: Color,
+    text_color: Color,
+    visible: bool
+}
+
+impl Legend {
+    pub const fn new() -> Self {
+        Self {
+            position: LegendPosition::TopRight,
+            background: Color::from_argb(0xC0, 0x10, 0x10, 0x10),
+            border: Color::from_rgb(0x60, 0x60, 0x60),
+            text_color: Color::from_rgb(0xE0, 0xE0, 0xE0),
+            visible: true
+        }
+    }
+
+    pub const fn with_position(mut self, position: LegendPosition) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub const fn with_colors(mut self, background: Color, border: Color, text: Color) -> Self {
+        self.background = background;
+        self.border = border;
+        self.text_color = text;
+        self
+    }
+
+    pub const fn with_visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    pub(crate) fn draw(&self, buffer: &mut Buffer, series: &[Series]) {
+        let entries: Vec<(&Series, &str)> = series.iter()
+            .filter_map(|series| series.name().map(|name| (series, name)))
+            .collect();
+
+        if !self.visible || entries.is_empty() {
+            return;
+        }
+
+        let font = BitmapFont::builtin();
+
+        let row_height = font.line_height() as f64 + PADDING / 2.0;
+        let text_width = entries.iter()
+            .map(|(_, name)| font.measure(name, 1).width)
+            .max()
+            .unwrap_or(0) as f64;
+
+        let box_size = Vec2::new(
+            2.0 * PADDING + SAMPLE_LENGTH + SAMPLE_GAP + text_width,
+            2.0 * PADDING + row_height * entries.len() as f64 - PADDING / 2.0
+        );
+
+        let (width, height) = (buffer.size.width as f64, buffer.size.height as f64);
+
+        let left = match self.position {
+            LegendPosition::TopLeft | LegendPosition::BottomLeft => MARGIN,
+            LegendPosition::TopRight | LegendPosition::BottomRight => width - MARGIN - box_size.x
+        };
+
+        let top = match self.position {
+            LegendPosition::TopLeft | LegendPosition::TopRight => MARGIN,
+            LegendPosition::BottomLeft | LegendPosition::BottomRight => height - MARGIN - box_size.y
+        };
+
+        // the border goes through pixel centers so it stays sharp
+        let corner = Vec2::new(left.round() + 0.5, top.round() + 0.5);
+        let rectangle = Vec2::new(corner, corner + box_size);
+
+        buffer.fill_rounded_rectangle(rectangle, CORNER_RADIUS, self.background);
+        buffer.draw_rounded_rectangle(rectangle, CORNER_RADIUS, &LineStyle::new(1.0), self.border);
+
+        for (index, (series, name)) in entries.into_iter().enumerate() {
+            let row_top = corner.y + PADDING + index as f64 * row_height;
+            // through pixel centers so the sample stays one pixel wide
+            let middle = (row_top + font.line_height() as f64 / 2.0).floor() + 0.5;
+
+            // and from pixel edge to pixel edge
+            let sample_start = Vec2::new(left.round() + PADDING, middle);
+            let sample_end = sample_start + Vec2::new(SAMPLE_LENGTH, 0.0);
+
+            buffer.draw_styled_line(sample_start, sample_end, series.line_style(), series.color());
+
+            let text_pos = Vec2::new((sample_end.x + SAMPLE_GAP) as isize, row_top as isize);
+
+            buffer.draw_text(name, text_pos, self.text_color, 1);
+        }
+    }
+}
+
+impl Default for Legend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simple_linear_algebra::vector::vec2::Vec2;
+    use simple_render::{color::Color, render::buffer::{Buffer, BufferSize}};
+
+    use crate::{legend::Legend, series::Series};
+
+    fn drawn(legend: Legend, series: &[Series]) -> Buffer {
+        let mut buffer = Buffer::init(BufferSize::new(100, 60));
+
+        buffer.fill(Color::BLACK);
+        legend.draw(&mut buffer, series);
+
+        buffer
+    }
+
+    fn count(buffer: &Buffer, matches: impl Fn(u8, u8, u8) -> bool) -> usize {
+        buffer.raw_buffer.0.iter()
+            .filter(|&&pixel| {
+                let (r, g, b) = Color::from_argb_u32(pixel).to_rgb();
+                matches(r, g, b)
+            })
+            .count()
+    }
+
+    fn is_black(buffer: &Buffer) -> bool {
+        buffer.raw_buffer.0.iter().all(|&pixel| pixel == Color::BLACK.0)
+    }
+
+    #[test]
+    fn named_series_only() {
+        let red = || Series::data(Vec::new(), Color::RED).with_name("a");
+        let green = || Series::data(Vec::new(), Color::GREEN);
+
+        let buffer = drawn(Legend::new(), &[red(), green()]);
+
+        // the box is in the top right corner and one row high
+        assert_ne!(buffer.get_point(Vec2::new(80, 13)), Color::BLACK.0);
+        assert_eq!(buffer.get_point(Vec2::new(20, 13)), Color::BLACK.0);
+        assert_eq!(buffer.get_point(Vec2::new(80, 40)), Color::BLACK.0);
+
+        // the sample is a sharp red line
+        assert_eq!(count(&buffer, |r, g, b| (r, g, b) == (255, 0, 0)), 24);
+        assert_eq!(count(&buffer, |r, g, _| g > 200 && r < 50), 0);
+
+        // a second row for a second named series
+        let buffer = drawn(Legend::new(), &[red(), green().with_name("b")]);
+
+        assert_ne!(buffer.get_point(Vec2::new(80, 40)), Color::BLACK.0);
+        assert!(count(&buffer, |r, g, _| g > 200 && r < 50) > 0);
+
+        assert!(is_black(&drawn(Legend::new(), &[green()])));
+        assert!(is_black(&drawn(Legend::new().with_visible(false), &[red()])));
+    }
+}
@@ -1,57 +1,218 @@
-use std::sync::{Arc, Mutex};
+use std::{path::PathBuf, sync::{Arc, Mutex}};
 
-use minifb::Window;
 use simple_linear_algebra::vector::vec2::Vec2;
-use simple_render::{color::Color, render::{Render, app_handler::{AppHandler, Event}}};
+use simple_render::{color::Color, render::{Render, app_handler::{AppHandler, Event}, backend::Backend, buffer::{Buffer, BufferSize}, headless::Headless, input::Key}};
 
+use crate::{axes::Axes, legend::Legend, series::Series, viewport::Viewport};
+
+pub mod viewport;
+pub mod axes;
+pub mod series;
+pub mod legend;
+mod sampling;
+
+// the part of the view one key press pans by
+const PAN_STEP: f64 = 0.05;
+// how much one key press or wheel step zooms in
+const ZOOM_STEP: f64 = 0.9;
+
+// Plots any number of series over the viewport, in the order they were added. Arrows or WASD pan,
+// +/- or the mouse wheel zoom around the cursor and R goes back to the viewport that was set
 pub struct Builder {
-    func: fn(isize) -> isize,
-    color: Color,
-    need_to_redraw: bool
+    series: Vec<Series>,
+    viewport: Viewport,
+    // where R goes back to
+    home: Viewport,
+    // the size of the last drawn buffer and the cursor in it, for zooming around the cursor
+    size: BufferSize,
+    cursor: Option<Vec2<f64>>,
+    axes: Axes,
+    legend: Legend,
+    background: Color,
+    need_to_redraw: bool,
+    screenshot_path: Option<PathBuf>,
+    take_screenshot: bool
 }
 
 impl Builder {
-    pub fn new(func: fn(isize) -> isize, color: Color) -> Self {
-        Self { func, color, need_to_redraw: true }
+    pub fn new() -> Self {
+        Self {
+            series: Vec::new(),
+            viewport: Viewport::default(),
+            home: Viewport::default(),
+            size: BufferSize::new(0, 0),
+            cursor: None,
+            axes: Axes::default(),
+            legend: Legend::default(),
+            background: Color::BLACK,
+            need_to_redraw: true,
+            screenshot_path: None,
+            take_screenshot: false
+        }
+    }
+
+    // a single unnamed function, like the builder used to plot
+    pub fn with_function<F: Fn(f64) -> f64 + Send + Sync + 'static>(func: F, color: Color) -> Self {
+        let mut builder = Self::new();
+        builder.add_series(Series::function(func, color));
+        builder
+    }
+
+    pub fn add_series(&mut self, series: Series) {
+        self.series.push(series);
+        self.need_to_redraw = true;
+    }
+
+    pub fn series(&self) -> &[Series] {
+        &self.series
+    }
+
+    pub fn clear_series(&mut self) {
+        self.series.clear();
+        self.need_to_redraw = true;
+    }
+
+    pub fn viewport(&self) -> Viewport {
+        self.viewport
+    }
+
+    // see `Viewport::normalized`, a viewport with bounds that are not finite is ignored
+    pub fn set_viewport(&mut self, viewport: Viewport) {
+        let Some(viewport) = viewport.normalized() else {
+            return;
+        };
+
+        self.viewport = viewport;
+        self.home = viewport;
+        self.need_to_redraw = true;
+    }
+
+    // the part of the view to move by, positive y moves up
+    pub fn pan(&mut self, fraction: Vec2<f64>) {
+        let offset = Vec2::new(fraction.x * self.viewport.width(), fraction.y * self.viewport.height());
+
+        self.change_view(self.viewport.translated(offset));
+    }
+
+    // factor < 1 zooms in, the point under the cursor stays in place
+    pub fn zoom(&mut self, factor: f64) {
+        let anchor = match self.cursor {
+            Some(cursor) if self.size.width != 0 && self.size.height != 0 => self.viewport.to_plane(cursor, self.size),
+            _ => self.viewport.center()
+        };
+
+        self.change_view(self.viewport.zoomed(factor, anchor));
+    }
+
+    pub fn reset_view(&mut self) {
+        self.change_view(self.home);
+    }
+
+    // redraws only if the view really changed
+    fn change_view(&mut self, viewport: Viewport) {
+        if viewport != self.viewport {
+            self.viewport = viewport;
+            self.need_to_redraw = true;
+        }
+    }
+
+    pub fn set_axes(&mut self, axes: Axes) {
+        self.axes = axes;
+        self.need_to_redraw = true;
+    }
+
+    pub fn set_legend(&mut self, legend: Legend) {
+        self.legend = legend;
+        self.need_to_redraw = true;
+    }
+
+    pub fn set_background(&mut self, background: Color) {
+        self.background = background;
+        self.need_to_redraw = true;
     }
 
-    pub fn run(self, fps: f64, window: Window) {
+    pub fn draw(&self, buffer: &mut Buffer) {
+        buffer.fill(self.background);
+
+        self.axes.draw(buffer, &self.viewport);
+
+        for series in &self.series {
+            series.draw(buffer, &self.viewport);
+        }
+
+        self.legend.draw(buffer, &self.series);
+    }
+
+    // F12 saves the shown plot to this path
+    pub fn set_screenshot_path<P: Into<PathBuf>>(&mut self, path: P) {
+        self.screenshot_path = Some(path.into());
+    }
+
+    pub fn run<B: Backend>(self, fps: f64, backend: B) {
 
         let clone = Arc::new(Mutex::new(self));
 
-        let mut render = Render::new(clone, fps, window);
+        let mut render = Render::new(clone, fps, backend);
+
+        // F3 shows the frame rate and timings
+        render.set_overlay_key(Key::F3);
 
         render.run();
     }
+
+    pub fn run_headless(self, size: BufferSize, frames: usize, keys: &[(usize, Key)]) -> Buffer {
+        let clone = Arc::new(Mutex::new(self));
+
+        let mut headless = Headless::new(clone, size, frames)
+            .with_keys(keys);
+
+        headless.run();
+
+        headless.into_buffer()
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AppHandler for Builder {
     fn event(&mut self, event: Event) {
         match event {
             Event::RedrawReqiest { buffer } => {
-                buffer.fill(Color::BLACK);
-
-                let mut vec2_vec = Vec::<Vec2<isize>>::with_capacity(buffer.size.width);
+                self.size = buffer.size;
+                self.draw(buffer);
+            }
 
-                for x in 1..buffer.size.width as isize {
-                    let y = buffer.size.height as isize - (self.func)(x);
+            // the viewport is stretched over the new size
+            Event::Resized { size } => {
+                self.size = size;
+                self.need_to_redraw = true;
+            }
 
-                    let vec2 = Vec2::new(x, y / 2);
+            Event::KeyPressed { key, repeat } => match key {
+                Key::Left | Key::A => self.pan(Vec2::new(-PAN_STEP, 0.0)),
+                Key::Right | Key::D => self.pan(Vec2::new(PAN_STEP, 0.0)),
+                Key::Up | Key::W => self.pan(Vec2::new(0.0, PAN_STEP)),
+                Key::Down | Key::S => self.pan(Vec2::new(0.0, -PAN_STEP)),
 
-                    vec2_vec.push(vec2);
-                }
+                Key::Equal | Key::NumPadPlus => self.zoom(ZOOM_STEP),
+                Key::Minus | Key::NumPadMinus => self.zoom(1.0 / ZOOM_STEP),
 
-                let mut iter = vec2_vec.iter().peekable();
+                Key::R if !repeat => self.reset_view(),
 
-                while let Some(vec) = iter.next() {
-                    if let Some(&f) = iter.peek() {
-                        buffer.draw_line(*vec, *f, self.color);
-                    }
-                }
+                Key::F12 if !repeat => self.take_screenshot = true,
 
-                self.need_to_redraw = true;
+                _ => ()
             }
 
+            Event::MouseMoved { pos } => self.cursor = Some(pos),
+
+            // positive y scrolls up, which zooms in
+            Event::MouseWheel { delta } => self.zoom(ZOOM_STEP.powf(delta.y)),
+
             _ => ()
         }
     }
@@ -63,4 +224,145 @@ impl AppHandler for Builder {
     fn redrawed(&mut self) {
         self.need_to_redraw = false;
     }
+
+    fn screenshot(&mut self) -> Option<PathBuf> {
+        if !std::mem::take(&mut self.take_screenshot) {
+            return None;
+        }
+
+        self.screenshot_path.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simple_linear_algebra::vector::vec2::Vec2;
+    use simple_render::{color::Color, render::{app_handler::{AppHandler, Event}, buffer::{Buffer, BufferSize}, input::Key}};
+
+    use crate::{Builder, axes::Axes, viewport::Viewport};
+
+    const SIZE: BufferSize = BufferSize::new(100, 100);
+
+    // the rows of a column the red line touches, the grey axes have green in them
+    fn line_rows(buffer: &Buffer, column: usize) -> Vec<usize> {
+        (0..buffer.size.height)
+            .filter(|&row| {
+                let pixel = buffer.raw_buffer.0[row * buffer.size.width + column];
+
+                pixel & 0x00FF_0000 != 0 && pixel & 0x0000_FF00 == 0
+            })
+            .collect()
+    }
+
+    // drawn once at SIZE with the cursor at `cursor`
+    fn builder(cursor: Vec2<f64>) -> Builder {
+        let mut builder = Builder::new();
+
+        builder.set_viewport(Viewport::from_ranges((-1.0, 3.0), (-2.0, 2.0)));
+        builder.event(Event::Resized { size: SIZE });
+        builder.event(Event::MouseMoved { pos: cursor });
+        builder.redrawed();
+
+        builder
+    }
+
+    fn press(builder: &mut Builder, key: Key) {
+        builder.event(Event::KeyPressed { key, repeat: false });
+    }
+
+    #[test]
+    fn headless_plot() {
+        let plot = |keys: &[(usize, Key)]| {
+            let mut builder = Builder::with_function(|_| 0.5, Color::RED);
+
+            builder.set_viewport(Viewport::from_ranges((-1.0, 1.0), (-1.0, 1.0)));
+            builder.set_axes(Axes::new().with_grid(false).with_labels(false));
+
+            builder.run_headless(SIZE, 6, keys)
+        };
+
+        let still = line_rows(&plot(&[]), 75);
+
+        assert!(!still.is_empty() && still.iter().all(|row| (23..=26).contains(row)), "{still:?}");
+
+        // five pans up by a twentieth of the view, the line moves down by a quarter of the height
+        let keys = (0..5).map(|frame| (frame, Key::Up)).collect::<Vec<_>>();
+        let panned = line_rows(&plot(&keys), 75);
+
+        assert!(!panned.is_empty() && panned.iter().all(|row| (48..=51).contains(row)), "{panned:?}");
+    }
+
+    #[test]
+    fn zoom_around_cursor() {
+        let cursor = Vec2::new(20.0, 70.0);
+        let mut builder = builder(cursor);
+
+        let under_cursor = builder.viewport().to_plane(cursor, SIZE);
+
+        press(&mut builder, Key::Equal);
+        builder.event(Event::MouseWheel { delta: Vec2::new(0.0, -3.0) });
+        press(&mut builder, Key::NumPadPlus);
+
+        let zoomed = builder.viewport();
+        let moved = zoomed.to_plane(cursor, SIZE) - under_cursor;
+
+        assert!(moved.x.abs() < 1e-12 && moved.y.abs() < 1e-12, "{moved:?}");
+        // in, out three steps and in again
+        assert!((zoomed.width() - 4.0 / 0.9).abs() < 1e-12, "{zoomed:?}");
+        assert!(builder.need_to_redraw());
+    }
+
+    #[test]
+    fn reset_view() {
+        let mut builder = builder(Vec2::new(50.0, 50.0));
+        let home = builder.viewport();
+
+        press(&mut builder, Key::Left);
+        press(&mut builder, Key::W);
+        press(&mut builder, Key::Minus);
+
+        assert_ne!(builder.viewport(), home);
+
+        press(&mut builder, Key::R);
+
+        assert_eq!(builder.viewport(), home);
+    }
+
+    #[test]
+    fn same_view_is_not_redrawn() {
+        let mut builder = builder(Vec2::new(50.0, 50.0));
+
+        builder.change_view(builder.viewport());
+        assert!(!builder.need_to_redraw());
+
+        // R at home changes nothing
+        press(&mut builder, Key::R);
+        assert!(!builder.need_to_redraw());
+
+        // neither does zooming past the limit
+        builder.set_viewport(Viewport::from_ranges((1.0, 1.0 + 1e-12), (0.0, 1.0)));
+        builder.redrawed();
+
+        press(&mut builder, Key::Equal);
+        assert!(!builder.need_to_redraw());
+
+        press(&mut builder, Key::Right);
+        assert!(builder.need_to_redraw());
+    }
+
+    #[test]
+    fn degenerate_viewport() {
+        let mut builder = Builder::new();
+
+        builder.set_viewport(Viewport::from_ranges((3.0, -1.0), (2.0, 2.0)));
+        assert_eq!(builder.viewport(), Viewport::from_ranges((-1.0, 3.0), (1.0, 3.0)));
+
+        builder.set_viewport(Viewport::from_ranges((0.0, f64::INFINITY), (0.0, 1.0)));
+        assert_eq!(builder.viewport(), Viewport::from_ranges((-1.0, 3.0), (1.0, 3.0)));
+
+        // R goes back to the normalized viewport
+        press(&mut builder, Key::D);
+        press(&mut builder, Key::R);
+        assert_eq!(builder.viewport(), Viewport::from_ranges((-1.0, 3.0), (1.0, 3.0)));
+    }
 }
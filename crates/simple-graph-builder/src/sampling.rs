@@ -0,0 +1,365 @@
+use simple_linear_algebra::vector::{Vector, vec2::Vec2};
+use simple_render::render::buffer::BufferSize;
+
+use crate::viewport::Viewport;
+
+// pixels between the first samples, subdivision fills in where the curve bends
+const INITIAL_SPACING: f64 = 8.0;
+// a bend whose middle is closer than this many pixels to the chord is not worth refining
+const MIN_DEVIATION: f64 = 0.25;
+// samples are never closer than this many pixels, finer wiggles can not be drawn anyway
+const MIN_SPACING: f64 = 0.25;
+// a finite step between two samples steeper than this many pixels is checked for a discontinuity
+const MIN_JUMP: f64 = 4.0;
+// halvings of a segment while looking for a discontinuity or the edge of the domain
+const MAX_BISECTIONS: usize = 48;
+// a side of a jump further than this many view heights from the view makes it an asymptote
+const ASYMPTOTE_DISTANCE: f64 = 100.0;
+
+// How densely a function is sampled
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Sampling {
+    // the largest turn of the line at a sample before the segments around it are halved, in radians
+    pub tolerance: f64,
+    // how many times the first segments may be halved
+    pub max_depth: usize,
+    // look for jumps instead of joining every pair of samples
+    pub discontinuities: bool
+}
+
+impl Default for Sampling {
+    fn default() -> Self {
+        Self { tolerance: 0.05, max_depth: 12, discontinuities: true }
+    }
+}
+
+// The finite parts of a function over the view and the x of the vertical asymptotes found between them
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Sampled {
+    pub parts: Vec<Vec<Vec2<f64>>>,
+    pub asymptotes: Vec<f64>
+}
+
+// where the line is broken between two samples
+#[derive(Default)]
+struct Break {
+    // the last finite point before the break and the first one after it
+    left: Option<Vec2<f64>>,
+    right: Option<Vec2<f64>>,
+    asymptote: Option<f64>
+}
+
+// Samples the function adaptively, where the line bends more than the tolerance the segments are halved
+// down to the maximum depth. Non-finite values always break the line. With `discontinuities` large jumps
+// are bisected to tell them from steep parts, and every break is moved as close to the discontinuity or
+// the edge of the domain as f64 allows
+pub(crate) fn sample_function(func: &dyn Fn(f64) -> f64, viewport: &Viewport, size: BufferSize, sampling: &Sampling) -> Sampled {
+    let mut sampled = Sampled::default();
+
+    if size.width == 0 || size.height == 0 {
+        return sampled;
+    }
+
+    let sampler = Sampler {
+        func,
+        viewport,
+        scale: Vec2::new(size.width as f64 / viewport.width(), size.height as f64 / viewport.height())
+    };
+
+    let points = sampler.subdivided(size.width as f64, sampling);
+
+    let mut part = Vec::new();
+
+    for (index, &point) in points.iter().enumerate() {
+        let found = match index.checked_sub(1).map(|previous| points[previous]) {
+            Some(previous) if sampling.discontinuities => sampler.find_break(previous, point),
+            Some(previous) if !(previous.y.is_finite() && point.y.is_finite()) => Some(Break::default()),
+            _ => None
+        };
+
+        if let Some(found) = found {
+            part.extend(found.left);
+
+            if part.len() > 1 {
+                sampled.parts.push(std::mem::take(&mut part));
+            }
+
+            part.clear();
+            part.extend(found.right);
+
+            sampled.asymptotes.extend(found.asymptote);
+        }
+
+        if point.y.is_finite() {
+            part.push(point);
+        }
+    }
+
+    if part.len() > 1 {
+        sampled.parts.push(part);
+    }
+
+    sampled
+}
+
+struct Sampler<'a> {
+    func: &'a dyn Fn(f64) -> f64,
+    viewport: &'a Viewport,
+    // pixels per unit
+    scale: Vec2<f64>
+}
+
+impl Sampler<'_> {
+    fn at(&self, x: f64) -> Vec2<f64> {
+        Vec2::new(x, (self.func)(x))
+    }
+
+    // sorted samples from one edge of the view to the other
+    fn subdivided(&self, width: f64, sampling: &Sampling) -> Vec<Vec2<f64>> {
+        let count = (width / INITIAL_SPACING).ceil().max(1.0) as usize;
+        let step = self.viewport.width() / count as f64;
+
+        let mut points = vec![self.at(self.viewport.min.x)];
+
+        for index in 0..count {
+            let start = *points.last().unwrap();
+            let end = self.at(self.viewport.min.x + step * (index + 1) as f64);
+            let middle = self.at((start.x + end.x) / 2.0);
+
+            self.subdivide(&mut points, [start, middle, end], sampling, 0);
+        }
+
+        points
+    }
+
+    // Pushes the samples after the start of `segment`. The quarter points are checked too, a narrow
+    // spike missed by the middle alone would be missed for good. Halving stops at the maximum depth
+    // or once the halves would put the samples closer than the minimum spacing
+    fn subdivide(&self, points: &mut Vec<Vec2<f64>>, segment: [Vec2<f64>; 3], sampling: &Sampling, depth: usize) {
+        let [start, middle, end] = segment;
+
+        let first = self.at((start.x + middle.x) / 2.0);
+        let third = self.at((middle.x + end.x) / 2.0);
+
+        // the halves are split into quarters again
+        let spacing = (end.x - start.x) * self.scale.x / 8.0;
+
+        let bent = depth < sampling.max_depth
+            && spacing >= MIN_SPACING
+            && first.x > start.x && third.x < end.x
+            && [[start, first, middle], [first, middle, third], [middle, third, end]]
+                .into_iter()
+                .any(|[a, b, c]| self.is_bent(a, b, c, sampling.tolerance));
+
+        if !bent {
+            points.extend([first, middle, third, end]);
+            return;
+        }
+
+        self.subdivide(points, [start, first, middle], sampling, depth + 1);
+        self.subdivide(points, [middle, third, end], sampling, depth + 1);
+    }
+
+    // Whether the line turns at `b` by more than the tolerance, measured on the screen. Non-finite
+    // values are left to the discontinuity search
+    fn is_bent(&self, a: Vec2<f64>, b: Vec2<f64>, c: Vec2<f64>, tolerance: f64) -> bool {
+        if !(a.y.is_finite() && b.y.is_finite() && c.y.is_finite()) {
+            return false;
+        }
+
+        let screen = |from: Vec2<f64>, to: Vec2<f64>| Vec2::new((to.x - from.x) * self.scale.x, (to.y - from.y) * self.scale.y);
+
+        let (before, after, chord) = (screen(a, b), screen(b, c), screen(a, c));
+
+        let turn = before.cross(after).abs().atan2(before.dot(after));
+        let deviation = chord.cross(before).abs() / chord.length();
+
+        turn > tolerance && deviation > MIN_DEVIATION
+    }
+
+    fn find_break(&self, mut left: Vec2<f64>, mut right: Vec2<f64>) -> Option<Break> {
+        match (left.y.is_finite(), right.y.is_finite()) {
+            (false, false) => return Some(Break::default()),
+            (true, false) => return Some(self.domain_edge(left, right, true)),
+            (false, true) => return Some(self.domain_edge(right, left, false)),
+            (true, true) if (right.y - left.y).abs() * self.scale.y <= MIN_JUMP => return None,
+            (true, true) => ()
+        }
+
+        // a steep but continuous function flattens out when halved, a discontinuity keeps its jump
+        for _ in 0..MAX_BISECTIONS {
+            let x = (left.x + right.x) / 2.0;
+
+            if x <= left.x || x >= right.x {
+                break;
+            }
+
+            let middle = self.at(x);
+
+            if !middle.y.is_finite() {
+                let before = self.domain_edge(left, middle, true);
+                let after = self.domain_edge(right, middle, false);
+
+                return Some(Break { left: before.left, right: after.right, asymptote: before.asymptote.or(after.asymptote) });
+            }
+
+            if (middle.y - left.y).abs() > (right.y - middle.y).abs() {
+                right = middle;
+            } else {
+                left = middle;
+            }
+
+            if (right.y - left.y).abs() * self.scale.y < 1.0 {
+                return None;
+            }
+        }
+
+        let asymptote = (self.is_unbounded(left.y) || self.is_unbounded(right.y))
+            .then_some((left.x + right.x) / 2.0);
+
+        Some(Break { left: Some(left), right: Some(right), asymptote })
+    }
+
+    // The finite point closest to where the function stops being finite, between a finite
+    // `inside` and a non-finite `outside`. `before` is true if the break comes after `inside`
+    fn domain_edge(&self, mut inside: Vec2<f64>, mut outside: Vec2<f64>, before: bool) -> Break {
+        for _ in 0..MAX_BISECTIONS {
+            let x = (inside.x + outside.x) / 2.0;
+
+            if x == inside.x || x == outside.x {
+                break;
+            }
+
+            let middle = self.at(x);
+
+            if middle.y.is_finite() {
+                inside = middle;
+            } else {
+                outside = middle;
+            }
+        }
+
+        // infinity is a pole, NaN is just outside the domain
+        let asymptote = (outside.y.is_infinite() || self.is_unbounded(inside.y)).then_some(outside.x);
+
+        match before {
+            true => Break { left: Some(inside), right: None, asymptote },
+            false => Break { left: None, right: Some(inside), asymptote }
+        }
+    }
+
+    fn is_unbounded(&self, y: f64) -> bool {
+        let margin = ASYMPTOTE_DISTANCE * self.viewport.height();
+
+        y > self.viewport.max.y + margin || y < self.viewport.min.y - margin
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, f64::consts::FRAC_PI_2};
+
+    use simple_linear_algebra::vector::vec2::Vec2;
+    use simple_render::render::buffer::BufferSize;
+
+    use crate::{sampling::{Break, Sampled, Sampler, Sampling, sample_function}, viewport::Viewport};
+
+    const SIZE: BufferSize = BufferSize::new(400, 300);
+
+    // the samples and how many times the function was called for them
+    fn sample(func: fn(f64) -> f64, viewport: Viewport) -> (Sampled, usize) {
+        let calls = Cell::new(0);
+
+        let counted = |x: f64| {
+            calls.set(calls.get() + 1);
+            func(x)
+        };
+
+        let sampled = sample_function(&counted, &viewport, SIZE, &Sampling::default());
+
+        (sampled, calls.get())
+    }
+
+    fn samples_between(sampled: &Sampled, min: f64, max: f64) -> usize {
+        sampled.parts.iter().flatten().filter(|point| point.x >= min && point.x < max).count()
+    }
+
+    #[test]
+    fn denser_where_bent() {
+        let (sampled, calls) = sample(|x| (1.0 / x).sin(), Viewport::from_ranges((-1.0, 1.0), (-1.5, 1.5)));
+
+        let near = samples_between(&sampled, -0.1, 0.1);
+        let far = samples_between(&sampled, 0.5, 0.7);
+
+        assert!(near > 4 * far, "{near} {far}");
+
+        // a quarter of a pixel apart at most
+        assert!(sampled.parts.iter().map(Vec::len).sum::<usize>() <= 4 * SIZE.width + 1);
+        assert!(calls <= 10 * SIZE.width, "{calls}");
+    }
+
+    #[test]
+    fn sample_budget() {
+        let (sampled, calls) = sample(|x| (1000.0 * x).sin(), Viewport::from_ranges((-1.0, 1.0), (-1.5, 1.5)));
+
+        assert_eq!(sampled.parts.len(), 1);
+        assert!(sampled.parts[0].len() <= 4 * SIZE.width + 1, "{}", sampled.parts[0].len());
+
+        // most calls go to telling the steep parts from jumps
+        assert!(calls <= 40 * SIZE.width, "{calls}");
+    }
+
+    // the break between two samples of `func` over a 10 by 10 view drawn 400 by 300 pixels
+    fn find_break(func: fn(f64) -> f64, left: f64, right: f64) -> Option<Break> {
+        let viewport = Viewport::from_ranges((-5.0, 5.0), (-5.0, 5.0));
+
+        let sampler = Sampler { func: &func, viewport: &viewport, scale: Vec2::new(40.0, 30.0) };
+
+        sampler.find_break(sampler.at(left), sampler.at(right))
+    }
+
+    #[test]
+    fn pole() {
+        let found = find_break(f64::tan, 1.5, 1.6).unwrap();
+
+        let (left, right) = (found.left.unwrap(), found.right.unwrap());
+
+        // pi / 2 rounded to f64 is still left of the pole
+        assert!(left.x <= FRAC_PI_2 && right.x > FRAC_PI_2 && right.x - left.x < 1e-12);
+        assert!(left.y > 1e6 && right.y < -1e6);
+        assert!((found.asymptote.unwrap() - FRAC_PI_2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn jump() {
+        let found = find_break(f64::floor, 0.9, 1.1).unwrap();
+
+        let (left, right) = (found.left.unwrap(), found.right.unwrap());
+
+        assert_eq!((left.y, right.y), (0.0, 1.0));
+        assert!(left.x < 1.0 && right.x >= 1.0 && right.x - left.x < 1e-12);
+        assert_eq!(found.asymptote, None);
+    }
+
+    #[test]
+    fn domain_edge() {
+        let found = find_break(f64::sqrt, -0.1, 0.1).unwrap();
+
+        let right = found.right.unwrap();
+
+        assert!(found.left.is_none());
+        assert!(right.x >= 0.0 && right.x < 1e-12);
+        assert_eq!(found.asymptote, None);
+
+        // the other way round the edge ends the line instead
+        let found = find_break(|x| (-x).sqrt(), -0.1, 0.1).unwrap();
+
+        assert!(found.right.is_none() && found.left.unwrap().x > -1e-12);
+    }
+
+    #[test]
+    fn steep_but_continuous() {
+        assert!(find_break(|x| (1e6 * x).atan(), -0.1, 0.1).is_none());
+        assert!(find_break(|x| x, -1.0, 1.0).is_none());
+    }
+}
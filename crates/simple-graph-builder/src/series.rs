@@ -0,0 +1,272 @@
+use simple_linear_algebra::vector::vec2::Vec2;
+use simple_render::{color::Color, render::{buffer::Buffer, stroke::LineStyle}};
+
+use crate::{sampling::{Sampling, sample_function}, viewport::Viewport};
+
+// how far outside the buffer the line is clipped, in buffer sizes, so its caps and joins stay out of view
+const CLIP_MARGIN: f64 = 0.5;
+
+// Where the points of a series come from
+pub enum SeriesSource {
+    // y = f(x), sampled more densely where it bends
+    Function(Box<dyn Fn(f64) -> f64 + Send + Sync>),
+    // points of the plane joined in order
+    Data(Vec<Vec2<f64>>)
+}
+
+// One line of the plot. Only named series get into the legend
+pub struct Series {
+    source: SeriesSource,
+    color: Color,
+    line_style: LineStyle,
+    name: Option<String>,
+    sampling: Sampling,
+    // dashed vertical lines where a function goes to infinity
+    asymptotes: bool
+}
+
+impl Series {
+    pub fn function<F: Fn(f64) -> f64 + Send + Sync + 'static>(func: F, color: Color) -> Self {
+        Self::new(SeriesSource::Function(Box::new(func)), color)
+    }
+
+    pub fn data<P: Into<Vec<Vec2<f64>>>>(points: P, color: Color) -> Self {
+        Self::new(SeriesSource::Data(points.into()), color)
+    }
+
+    pub fn new(source: SeriesSource, color: Color) -> Self {
+        Self { source, color, line_style: LineStyle::default(), name: None, sampling: Sampling::default(), asymptotes: false }
+    }
+
+    pub fn with_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_line_style(mut self, line_style: LineStyle) -> Self {
+        self.line_style = line_style;
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    // look for jumps in functions instead of joining every pair of samples
+    pub fn with_discontinuities(mut self, discontinuities: bool) -> Self {
+        self.sampling.discontinuities = discontinuities;
+        self
+    }
+
+    // the largest turn of the line at a sample of a function, in radians, sharper turns are sampled more densely
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.sampling.tolerance = tolerance;
+        self
+    }
+
+    // how many times a segment between the first samples of a function may be halved
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.sampling.max_depth = max_depth;
+        self
+    }
+
+    pub fn with_asymptotes(mut self, asymptotes: bool) -> Self {
+        self.asymptotes = asymptotes;
+        self
+    }
+
+    pub fn source(&self) -> &SeriesSource {
+        &self.source
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    pub fn line_style(&self) -> &LineStyle {
+        &self.line_style
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub(crate) fn draw(&self, buffer: &mut Buffer, viewport: &Viewport) {
+        let size = buffer.size;
+
+        let (parts, asymptotes) = match &self.source {
+            SeriesSource::Function(func) => {
+                let sampled = sample_function(func.as_ref(), viewport, size, &self.sampling);
+                (sampled.parts, sampled.asymptotes)
+            }
+            SeriesSource::Data(points) => (finite_parts(points), Vec::new())
+        };
+
+        if self.asymptotes {
+            let style = LineStyle::new(1.0).with_dash(&[6.0, 4.0], 0.0);
+            let color = self.color.fade(0x80);
+
+            for x in asymptotes {
+                // through pixel centers so the line stays one pixel wide
+                let column = viewport.to_screen(Vec2::new(x, 0.0), size).x.floor() + 0.5;
+
+                buffer.draw_styled_line(Vec2::new(column, 0.0), Vec2::new(column, size.height as f64), &style, color);
+            }
+        }
+
+        let margin = Vec2::new(size.width as f64, size.height as f64) * CLIP_MARGIN;
+        let (min, max) = (margin * -1.0, Vec2::new(size.width as f64, size.height as f64) + margin);
+
+        for part in parts {
+            let screen: Vec<Vec2<f64>> = part.into_iter()
+                .map(|point| viewport.to_screen(point, size))
+                .collect();
+
+            for clipped in clip_polyline(&screen, min, max) {
+                buffer.draw_polyline(&clipped, &self.line_style, self.color);
+            }
+        }
+    }
+}
+
+// values that are not numbers leave a gap in the line
+fn finite_parts(points: &[Vec2<f64>]) -> Vec<Vec<Vec2<f64>>> {
+    points.split(|point| !point.x.is_finite() || !point.y.is_finite())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.to_vec())
+        .collect()
+}
+
+// The parts of the polyline inside the rectangle. Far away points would make strokes and dashes
+// walk millions of pixels outside the buffer
+fn clip_polyline(points: &[Vec2<f64>], min: Vec2<f64>, max: Vec2<f64>) -> Vec<Vec<Vec2<f64>>> {
+    let mut parts = Vec::new();
+    let mut part: Vec<Vec2<f64>> = Vec::new();
+
+    for segment in points.windows(2) {
+        let Some((start, end)) = clip_segment(segment[0], segment[1], min, max) else {
+            continue;
+        };
+
+        if part.last() != Some(&start) {
+            if part.len() > 1 {
+                parts.push(std::mem::take(&mut part));
+            }
+
+            part.clear();
+            part.push(start);
+        }
+
+        part.push(end);
+    }
+
+    if part.len() > 1 {
+        parts.push(part);
+    }
+
+    parts
+}
+
+// Liang-Barsky
+fn clip_segment(start: Vec2<f64>, end: Vec2<f64>, min: Vec2<f64>, max: Vec2<f64>) -> Option<(Vec2<f64>, Vec2<f64>)> {
+    let delta = end - start;
+
+    let (mut from, mut to) = (0.0, 1.0);
+
+    for (p, q) in [
+        (-delta.x, start.x - min.x),
+        (delta.x, max.x - start.x),
+        (-delta.y, start.y - min.y),
+        (delta.y, max.y - start.y)
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+
+            continue;
+        }
+
+        let t = q / p;
+
+        if p < 0.0 {
+            from = f64::max(from, t);
+        } else {
+            to = f64::min(to, t);
+        }
+    }
+
+    if from > to {
+        return None;
+    }
+
+    // unclipped ends are kept exactly so the parts join up again
+    let clipped_start = if from == 0.0 { start } else { start + delta * from };
+    let clipped_end = if to == 1.0 { end } else { start + delta * to };
+
+    Some((clipped_start, clipped_end))
+}
+
+#[cfg(test)]
+mod tests {
+    use simple_linear_algebra::vector::vec2::Vec2;
+
+    use crate::series::{clip_polyline, clip_segment, finite_parts};
+
+    const MIN: Vec2<f64> = Vec2::new(0.0, 0.0);
+    const MAX: Vec2<f64> = Vec2::new(10.0, 10.0);
+
+    fn clipped(start: (f64, f64), end: (f64, f64)) -> Option<(Vec2<f64>, Vec2<f64>)> {
+        clip_segment(Vec2::new(start.0, start.1), Vec2::new(end.0, end.1), MIN, MAX)
+    }
+
+    #[test]
+    fn segment_clipping() {
+        // inside
+        assert_eq!(clipped((1.0, 1.0), (9.0, 5.0)), Some((Vec2::new(1.0, 1.0), Vec2::new(9.0, 5.0))));
+
+        // outside, next to an edge and beyond a corner
+        assert_eq!(clipped((-5.0, -1.0), (20.0, -1.0)), None);
+        assert_eq!(clipped((-5.0, 12.0), (-1.0, 20.0)), None);
+        assert_eq!(clipped((8.0, 13.0), (13.0, 8.0)), None);
+
+        // crossing
+        assert_eq!(clipped((-5.0, 5.0), (15.0, 5.0)), Some((Vec2::new(0.0, 5.0), Vec2::new(10.0, 5.0))));
+        assert_eq!(clipped((12.0, 12.0), (-2.0, -2.0)), Some((Vec2::new(10.0, 10.0), Vec2::new(0.0, 0.0))));
+        assert_eq!(clipped((5.0, 5.0), (5.0, 20.0)), Some((Vec2::new(5.0, 5.0), Vec2::new(5.0, 10.0))));
+
+        // parallel to an edge, on it and just outside
+        assert_eq!(clipped((0.0, -5.0), (0.0, 15.0)), Some((Vec2::new(0.0, 0.0), Vec2::new(0.0, 10.0))));
+        assert_eq!(clipped((-1.0, -5.0), (-1.0, 15.0)), None);
+        assert_eq!(clipped((2.0, 10.5), (8.0, 10.5)), None);
+    }
+
+    #[test]
+    fn polyline_clipping() {
+        let points = |points: &[(f64, f64)]| points.iter().map(|&(x, y)| Vec2::new(x, y)).collect::<Vec<_>>();
+
+        // the clipped segments share their ends, so they are joined again
+        let inside = points(&[(1.0, 1.0), (5.0, 5.0), (9.0, 1.0)]);
+        assert_eq!(clip_polyline(&inside, MIN, MAX), vec![inside.clone()]);
+
+        let crossing = points(&[(-5.0, 5.0), (5.0, 5.0), (5.0, 8.0), (15.0, 8.0)]);
+        assert_eq!(clip_polyline(&crossing, MIN, MAX), vec![points(&[(0.0, 5.0), (5.0, 5.0), (5.0, 8.0), (10.0, 8.0)])]);
+
+        // leaving and coming back splits the line
+        let leaving = points(&[(1.0, 5.0), (5.0, 15.0), (9.0, 5.0)]);
+        assert_eq!(clip_polyline(&leaving, MIN, MAX), vec![points(&[(1.0, 5.0), (3.0, 10.0)]), points(&[(7.0, 10.0), (9.0, 5.0)])]);
+
+        assert!(clip_polyline(&points(&[(-5.0, -5.0), (-1.0, 20.0)]), MIN, MAX).is_empty());
+    }
+
+    #[test]
+    fn data_split_on_non_finite_points() {
+        let point = |x: f64| Vec2::new(x, x);
+
+        let data = [point(0.0), point(1.0), Vec2::new(2.0, f64::NAN), point(3.0), Vec2::new(f64::INFINITY, 4.0), Vec2::new(5.0, f64::NEG_INFINITY), point(6.0), point(7.0)];
+
+        assert_eq!(finite_parts(&data), vec![vec![point(0.0), point(1.0)], vec![point(3.0)], vec![point(6.0), point(7.0)]]);
+        assert!(finite_parts(&[Vec2::new(f64::NAN, 0.0)]).is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+use simple_linear_algebra::vector::vec2::Vec2;
+use simple_render::render::buffer::BufferSize;
+
+// smaller than this times the coordinates and f64 cannot tell the pixels apart
+const MIN_RELATIVE_SIZE: f64 = 1e-12;
+const MAX_SIZE: f64 = 1e100;
+
+// The part of the plane shown in the buffer. It is stretched over the whole buffer, so it stays the same
+// part of the plane whatever the window size is
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    // bottom left corner
+    pub min: Vec2<f64>,
+    // top right corner
+    pub max: Vec2<f64>
+}
+
+impl Viewport {
+    pub const fn new(min: Vec2<f64>, max: Vec2<f64>) -> Self {
+        Self { min, max }
+    }
+
+    // the ranges are taken as they are, see `normalized`
+    pub const fn from_ranges(x: (f64, f64), y: (f64, f64)) -> Self {
+        Self::new(Vec2::new(x.0, y.0), Vec2::new(x.1, y.1))
+    }
+
+    // Inverted ranges are swapped and empty ones widened around their value. None if a bound is not finite
+    pub fn normalized(&self) -> Option<Self> {
+        let range = |min: f64, max: f64| {
+            if !(min.is_finite() && max.is_finite()) {
+                return None;
+            }
+
+            let (min, max) = (min.min(max), min.max(max));
+
+            if min < max {
+                return Some((min, max));
+            }
+
+            let half = 0.5 * min.abs().max(1.0);
+
+            Some((min - half, max + half))
+        };
+
+        let (min_x, max_x) = range(self.min.x, self.max.x)?;
+        let (min_y, max_y) = range(self.min.y, self.max.y)?;
+
+        Some(Self::from_ranges((min_x, max_x), (min_y, max_y)))
+    }
+
+    pub fn width(&self) -> f64 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f64 {
+        self.max.y - self.min.y
+    }
+
+    // a point of the plane in buffer pixels, y grows down in the buffer
+    pub fn to_screen(&self, point: Vec2<f64>, size: BufferSize) -> Vec2<f64> {
+        Vec2::new(
+            (point.x - self.min.x) / self.width() * size.width as f64,
+            (self.max.y - point.y) / self.height() * size.height as f64
+        )
+    }
+
+    pub fn to_plane(&self, screen: Vec2<f64>, size: BufferSize) -> Vec2<f64> {
+        Vec2::new(
+            self.min.x + screen.x / size.width as f64 * self.width(),
+            self.max.y - screen.y / size.height as f64 * self.height()
+        )
+    }
+
+    pub fn center(&self) -> Vec2<f64> {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn translated(&self, offset: Vec2<f64>) -> Self {
+        Self::new(self.min + offset, self.max + offset)
+    }
+
+    // Factor < 1 zooms in, the anchor stays at the same place in the buffer. Zooming stops before
+    // the size gets too small for the precision of f64 or too large to be finite
+    pub fn zoomed(&self, factor: f64, anchor: Vec2<f64>) -> Self {
+        let zoomed = Self::new(anchor + (self.min - anchor) * factor, anchor + (self.max - anchor) * factor);
+
+        let magnitude = [zoomed.min.x, zoomed.min.y, zoomed.max.x, zoomed.max.y]
+            .into_iter()
+            .fold(1.0, |max, value| f64::max(max, value.abs()));
+
+        let smallest = zoomed.width().min(zoomed.height());
+        let largest = zoomed.width().max(zoomed.height());
+
+        if !(smallest > magnitude * MIN_RELATIVE_SIZE && largest < MAX_SIZE) {
+            return *self;
+        }
+
+        zoomed
+    }
+
+    // the x of the plane in the middle of the pixel column
+    pub fn column_x(&self, column: usize, size: BufferSize) -> f64 {
+        self.min.x + (column as f64 + 0.5) / size.width as f64 * self.width()
+    }
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self::from_ranges((-10.0, 10.0), (-10.0, 10.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simple_linear_algebra::vector::vec2::Vec2;
+
+    use crate::viewport::Viewport;
+
+    #[test]
+    fn zoom_limits() {
+        let anchor = Vec2::new(0.5, 0.5);
+
+        let viewport = Viewport::from_ranges((0.0, 1.0), (0.0, 1.0));
+        assert_eq!(viewport.zoomed(0.5, anchor), Viewport::from_ranges((0.25, 0.75), (0.25, 0.75)));
+
+        // smaller than f64 can resolve next to the coordinates
+        assert_eq!(viewport.zoomed(1e-13, anchor), viewport);
+
+        let far = Viewport::from_ranges((1e6, 1e6 + 1.0), (0.0, 1.0));
+        assert_eq!(far.zoomed(1e-6, far.center()), far);
+        assert_ne!(far.zoomed(1e-5, far.center()), far);
+
+        // larger than the maximum size
+        let large = Viewport::from_ranges((-1e99, 1e99), (0.0, 1.0));
+        assert_eq!(large.zoomed(10.0, Vec2::new(0.0, 0.5)), large);
+        assert_eq!(viewport.zoomed(f64::INFINITY, anchor), viewport);
+    }
+
+    #[test]
+    fn normalized() {
+        let viewport = Viewport::from_ranges((-1.0, 2.0), (0.0, 5.0));
+        assert_eq!(viewport.normalized(), Some(viewport));
+
+        assert_eq!(Viewport::from_ranges((2.0, -1.0), (5.0, 0.0)).normalized(), Some(viewport));
+
+        assert_eq!(Viewport::from_ranges((0.0, 0.0), (4.0, 4.0)).normalized(), Some(Viewport::from_ranges((-0.5, 0.5), (2.0, 6.0))));
+
+        assert_eq!(Viewport::from_ranges((0.0, f64::NAN), (0.0, 1.0)).normalized(), None);
+        assert_eq!(Viewport::from_ranges((0.0, 1.0), (f64::NEG_INFINITY, 1.0)).normalized(), None);
+    }
+}
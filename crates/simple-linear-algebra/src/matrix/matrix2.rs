@@ -1,4 +1,4 @@
-use std::ops::{Add, Mul, Neg};
+use std::ops::{Add, Div, Mul, Neg, Sub};
 
 use crate::{matrix::Unit, num_traits::{NegOne, One, SinCos, Zero}, vector::{AxisUnits, vec2::Vec2}};
 
@@ -20,6 +20,45 @@ impl<T: Copy> Matrix2<T> {
     }
 }
 
+impl<T: Copy
+    + PartialEq
+    + Zero
+    + One
+    + Add<Output = T>
+    + Sub<Output = T>
+    + Mul<Output = T>
+    + Div<Output = T>
+    + Neg<Output = T>
+> Matrix2<T> {
+    pub fn transpose(&self) -> Self {
+        Self::row_major_new(self.i, self.j)
+    }
+
+    pub fn determinant(&self) -> T {
+        self.i.x * self.j.y - self.j.x * self.i.y
+    }
+
+    // None for a singular matrix
+    pub fn try_inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+
+        if det == T::ZERO {
+            return None;
+        }
+
+        let inv_det = T::ONE / det;
+
+        let i = Vec2::new(self.j.y, -self.i.y) * inv_det;
+        let j = Vec2::new(-self.j.x, self.i.x) * inv_det;
+
+        Some(Self::new(i, j))
+    }
+
+    pub fn inverse(&self) -> Self {
+        self.try_inverse().expect("the matrix is singular")
+    }
+}
+
 impl<T: Copy + SinCos + NegOne + Neg<Output = T>> Matrix2<T> {
     pub fn rotate_matrix(rad: T) -> Self {
         let (sin, cos) = rad.sin_cos();
@@ -60,3 +99,39 @@ impl<T: Copy + Add<Output = T> + Mul<Output = T>> Mul<Vec2<T>> for Matrix2<T> {
         self.i * rhs.x + self.j * rhs.y
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{matrix::{Unit, matrix2::Matrix2}, vector::vec2::Vec2};
+
+    #[test]
+    fn transpose() {
+        let matrix = Matrix2::row_major_new(Vec2::new(1.0, 2.0), Vec2::new(3.0, 4.0));
+
+        assert_eq!(matrix.transpose(), Matrix2::row_major_new(Vec2::new(1.0, 3.0), Vec2::new(2.0, 4.0)));
+    }
+
+    #[test]
+    fn determinant() {
+        let matrix = Matrix2::row_major_new(Vec2::new(1.0, 2.0), Vec2::new(3.0, 4.0));
+
+        assert_eq!(matrix.determinant(), -2.0);
+        assert_eq!(Matrix2::<f64>::UNIT.determinant(), 1.0);
+    }
+
+    #[test]
+    fn inverse() {
+        let matrix = Matrix2::row_major_new(Vec2::new(2.0, 1.0), Vec2::new(4.0, 4.0));
+        let inverse = matrix.inverse();
+
+        assert_eq!(inverse, Matrix2::row_major_new(Vec2::new(1.0, -0.25), Vec2::new(-1.0, 0.5)));
+        assert_eq!(matrix * inverse, Matrix2::UNIT);
+    }
+
+    #[test]
+    fn singular() {
+        let matrix = Matrix2::row_major_new(Vec2::new(1.0, 2.0), Vec2::new(2.0, 4.0));
+
+        assert_eq!(matrix.try_inverse(), None);
+    }
+}
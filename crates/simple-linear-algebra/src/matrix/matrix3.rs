@@ -1,4 +1,4 @@
-use std::ops::{Add, Mul};
+use std::ops::{Add, Div, Mul, Sub};
 
 use crate::{matrix::Unit, num_traits::{One, Zero}, vector::{AxisUnits, vec3::Vec3}};
 
@@ -22,6 +22,49 @@ impl<T: Copy> Matrix3<T> {
     }
 }
 
+impl<T: Copy
+    + PartialEq
+    + Zero
+    + One
+    + Add<Output = T>
+    + Sub<Output = T>
+    + Mul<Output = T>
+    + Div<Output = T>
+> Matrix3<T> {
+    pub fn transpose(&self) -> Self {
+        Self::row_major_new(self.i, self.j, self.k)
+    }
+
+    // scalar triple product of the columns
+    pub fn determinant(&self) -> T {
+        let cross = self.j.cross(self.k);
+
+        self.i.x * cross.x + self.i.y * cross.y + self.i.z * cross.z
+    }
+
+    // None for a singular matrix
+    pub fn try_inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+
+        if det == T::ZERO {
+            return None;
+        }
+
+        let inv_det = T::ONE / det;
+
+        // the rows of the inverse are the cross products of the columns
+        Some(Self::row_major_new(
+            self.j.cross(self.k) * inv_det,
+            self.k.cross(self.i) * inv_det,
+            self.i.cross(self.j) * inv_det
+        ))
+    }
+
+    pub fn inverse(&self) -> Self {
+        self.try_inverse().expect("the matrix is singular")
+    }
+}
+
 impl<T: Copy + Zero + One> Unit for Matrix3<T> {
     const UNIT: Self = Self::new(Vec3::X, Vec3::Y, Vec3::Z);
 }
@@ -55,3 +98,54 @@ impl<T: Copy + Add<Output = T> + Mul<Output = T>> Mul<Vec3<T>> for Matrix3<T> {
         self.i * rhs.x + self.j * rhs.y + self.k * rhs.z
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{matrix::{Unit, matrix3::Matrix3}, vector::{AxisUnits, vec3::Vec3}};
+
+    fn matrix() -> Matrix3<f64> {
+        Matrix3::row_major_new(
+            Vec3::new(2.0, 0.0, 1.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(0.0, 3.0, 1.0)
+        )
+    }
+
+    #[test]
+    fn transpose() {
+        let transposed = matrix().transpose();
+
+        assert_eq!(transposed.i, Vec3::new(2.0, 0.0, 1.0));
+        assert_eq!(transposed.transpose(), matrix());
+    }
+
+    #[test]
+    fn determinant() {
+        assert_eq!(matrix().determinant(), 5.0);
+        assert_eq!(matrix().transpose().determinant(), 5.0);
+    }
+
+    #[test]
+    fn inverse() {
+        let product = matrix() * matrix().inverse();
+
+        for (column, unit) in [(product.i, Vec3::X), (product.j, Vec3::Y), (product.k, Vec3::Z)] {
+            let diff = column - unit;
+
+            assert!(diff.x.abs() < 1e-12 && diff.y.abs() < 1e-12 && diff.z.abs() < 1e-12);
+        }
+
+        assert_eq!(Matrix3::<f64>::UNIT.inverse(), Matrix3::UNIT);
+    }
+
+    #[test]
+    fn singular() {
+        let matrix = Matrix3::row_major_new(
+            Vec3::new(1.0, 2.0, 3.0),
+            Vec3::new(4.0, 5.0, 6.0),
+            Vec3::new(5.0, 7.0, 9.0)
+        );
+
+        assert_eq!(matrix.try_inverse(), None);
+    }
+}
@@ -1,6 +1,6 @@
-use std::ops::{Add, Mul};
+use std::ops::{Add, Div, Mul, Neg, Sub};
 
-use crate::{matrix::Unit, num_traits::{One, Zero}, vector::{AxisUnits, vec4::Vec4}};
+use crate::{matrix::{Unit, matrix3::Matrix3}, num_traits::{One, Zero}, vector::{AxisUnits, vec4::Vec4}};
 
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
 pub struct Matrix4<T> {
@@ -24,6 +24,121 @@ impl<T: Copy> Matrix4<T> {
     }
 }
 
+impl<T: Copy
+    + PartialEq
+    + Zero
+    + One
+    + Add<Output = T>
+    + Sub<Output = T>
+    + Mul<Output = T>
+    + Div<Output = T>
+    + Neg<Output = T>
+> Matrix4<T> {
+    pub fn transpose(&self) -> Self {
+        Self::row_major_new(self.i, self.j, self.k, self.w)
+    }
+
+    // 2x2 minors of the first two and the last two columns
+    fn minors(&self) -> ([T; 6], [T; 6]) {
+        let Self { i: a, j: b, k: c, w: d } = *self;
+
+        let s = [
+            a.x * b.y - b.x * a.y,
+            a.x * b.z - b.x * a.z,
+            a.x * b.w - b.x * a.w,
+            a.y * b.z - b.y * a.z,
+            a.y * b.w - b.y * a.w,
+            a.z * b.w - b.z * a.w
+        ];
+
+        let c = [
+            c.x * d.y - d.x * c.y,
+            c.x * d.z - d.x * c.z,
+            c.x * d.w - d.x * c.w,
+            c.y * d.z - d.y * c.z,
+            c.y * d.w - d.y * c.w,
+            c.z * d.w - d.z * c.w
+        ];
+
+        (s, c)
+    }
+
+    pub fn determinant(&self) -> T {
+        let (s, c) = self.minors();
+
+        s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0]
+    }
+
+    // Laplace expansion over the minors, None for a singular matrix
+    pub fn try_inverse(&self) -> Option<Self> {
+        let (s, c) = self.minors();
+
+        let det = s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0];
+
+        if det == T::ZERO {
+            return None;
+        }
+
+        let inv_det = T::ONE / det;
+
+        let Self { i: a, j: b, k: m, w: d } = *self;
+
+        let i = Vec4::new(
+            b.y * c[5] - b.z * c[4] + b.w * c[3],
+            -a.y * c[5] + a.z * c[4] - a.w * c[3],
+            d.y * s[5] - d.z * s[4] + d.w * s[3],
+            -m.y * s[5] + m.z * s[4] - m.w * s[3]
+        );
+
+        let j = Vec4::new(
+            -b.x * c[5] + b.z * c[2] - b.w * c[1],
+            a.x * c[5] - a.z * c[2] + a.w * c[1],
+            -d.x * s[5] + d.z * s[2] - d.w * s[1],
+            m.x * s[5] - m.z * s[2] + m.w * s[1]
+        );
+
+        let k = Vec4::new(
+            b.x * c[4] - b.y * c[2] + b.w * c[0],
+            -a.x * c[4] + a.y * c[2] - a.w * c[0],
+            d.x * s[4] - d.y * s[2] + d.w * s[0],
+            -m.x * s[4] + m.y * s[2] - m.w * s[0]
+        );
+
+        let w = Vec4::new(
+            -b.x * c[3] + b.y * c[1] - b.z * c[0],
+            a.x * c[3] - a.y * c[1] + a.z * c[0],
+            -d.x * s[3] + d.y * s[1] - d.z * s[0],
+            m.x * s[3] - m.y * s[1] + m.z * s[0]
+        );
+
+        Some(Self::new(i * inv_det, j * inv_det, k * inv_det, w * inv_det))
+    }
+
+    pub fn inverse(&self) -> Self {
+        self.try_inverse().expect("the matrix is singular")
+    }
+
+    // Only valid when the bottom row is (0, 0, 0, 1), e.g. rotations, scales and displacements.
+    // Inverts the 3x3 part and moves the displacement back by it
+    pub fn try_affine_inverse(&self) -> Option<Self> {
+        let linear = Matrix3::new(self.i.into_vec3(), self.j.into_vec3(), self.k.into_vec3())
+            .try_inverse()?;
+
+        let displacement = -(linear * self.w.into_vec3());
+
+        Some(Self::new(
+            linear.i.extend_to_vec4(T::ZERO),
+            linear.j.extend_to_vec4(T::ZERO),
+            linear.k.extend_to_vec4(T::ZERO),
+            displacement.extend_to_vec4(T::ONE)
+        ))
+    }
+
+    pub fn affine_inverse(&self) -> Self {
+        self.try_affine_inverse().expect("the matrix is singular")
+    }
+}
+
 impl<T: Copy + Zero + One> Unit for Matrix4<T> {
     const UNIT: Self = Self::new(Vec4::X, Vec4::Y, Vec4::Z, Vec4::W);
 }
@@ -62,6 +177,8 @@ impl<T: Copy + Add<Output = T> + Mul<Output = T>> Mul<Vec4<T>> for Matrix4<T> {
     }
 }
 
+// Projections for column vectors into clip space where -w <= x, y, z <= w inside the view volume.
+// Right handed cameras look along -z, left handed ones along +z. The fov is vertical, in degrees
 impl Matrix4<f64> {
     pub fn persp_rh_matrix(fov: f64, aspect: f64, near: f64, far: f64) -> Matrix4<f64> {
         let f = 1.0 / (fov.to_radians() / 2.0).tan();
@@ -69,21 +186,143 @@ impl Matrix4<f64> {
         Matrix4::row_major_new(
             Vec4::new(f / aspect, 0.0, 0.0, 0.0),
             Vec4::new(0.0, f, 0.0, 0.0),
-            Vec4::new(0.0, 0.0, (far + near) / (near - far), -1.0),
-            Vec4::new(0.0, 0.0, (2.0 * far * near) / (near - far), 0.0)
+            Vec4::new(0.0, 0.0, (far + near) / (near - far), (2.0 * far * near) / (near - far)),
+            Vec4::new(0.0, 0.0, -1.0, 0.0)
         )
     }
 
     pub fn persp_lh_matrix(fov: f64, aspect: f64, near: f64, far: f64) -> Matrix4<f64> {
-        let f = (fov.to_radians() / 2.0).tan();
+        let f = 1.0 / (fov.to_radians() / 2.0).tan();
+
+        Matrix4::row_major_new(
+            Vec4::new(f / aspect, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, f, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, (far + near) / (far - near), (-2.0 * far * near) / (far - near)),
+            Vec4::new(0.0, 0.0, 1.0, 0.0)
+        )
+    }
+
+    pub fn ortho_rh_matrix(width: f64, height: f64, near: f64, far: f64) -> Matrix4<f64> {
+        Matrix4::row_major_new(
+            Vec4::new(2.0 / width, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, 2.0 / height, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, -2.0 / (far - near), -(far + near) / (far - near)),
+            Vec4::new(0.0, 0.0, 0.0, 1.0)
+        )
+    }
+
+    pub fn ortho_lh_matrix(width: f64, height: f64, near: f64, far: f64) -> Matrix4<f64> {
+        Matrix4::row_major_new(
+            Vec4::new(2.0 / width, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, 2.0 / height, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, 2.0 / (far - near), -(far + near) / (far - near)),
+            Vec4::new(0.0, 0.0, 0.0, 1.0)
+        )
+    }
+}
 
-        let f_n = far - near;
+#[cfg(test)]
+mod tests {
+    use crate::{matrix::{Unit, matrix4::Matrix4}, vector::{Vector, vec3::Vec3, vec4::Vec4}};
 
+    // unimodular, so the inverse has integer entries
+    fn matrix() -> Matrix4<f64> {
         Matrix4::row_major_new(
-            Vec4::new(1.0/f, 0.0, 0.0, 0.0),
-            Vec4::new(0.0, aspect / f, 0.0, 0.0),
-            Vec4::new(0.0, 0.0, far / f_n, 1.0),
-            Vec4::new(0.0, 0.0, -(near * far) / f_n, 0.0)
+            Vec4::new(1.0, 2.0, 0.0, 1.0),
+            Vec4::new(2.0, 5.0, 3.0, 2.0),
+            Vec4::new(0.0, 1.0, 4.0, 4.0),
+            Vec4::new(1.0, 2.0, 2.0, 10.0)
         )
     }
+
+    #[test]
+    fn transpose() {
+        let transposed = matrix().transpose();
+
+        assert_eq!(transposed.i, Vec4::new(1.0, 2.0, 0.0, 1.0));
+        assert_eq!(transposed.transpose(), matrix());
+    }
+
+    #[test]
+    fn determinant() {
+        assert_eq!(matrix().determinant(), 1.0);
+        assert_eq!(Matrix4::<f64>::UNIT.determinant(), 1.0);
+    }
+
+    #[test]
+    fn inverse() {
+        let inverse = matrix().inverse();
+
+        assert_eq!(matrix() * inverse, Matrix4::UNIT);
+        assert_eq!(inverse * matrix(), Matrix4::UNIT);
+    }
+
+    #[test]
+    fn singular() {
+        let mut matrix = matrix();
+        matrix.w = matrix.i + matrix.k;
+
+        assert_eq!(matrix.try_inverse(), None);
+
+        let flat = Vec3::new(1.0, 2.0, 3.0).to_displacement_matrix() * Matrix4::row_major_new(
+            Vec4::new(1.0, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, 1.0, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, 0.0, 1.0)
+        );
+
+        assert_eq!(flat.try_affine_inverse(), None);
+    }
+
+    #[test]
+    fn affine_inverse() {
+        let matrix = Matrix4::row_major_new(
+            Vec4::new(0.0, -2.0, 0.0, 1.0),
+            Vec4::new(1.0, 0.0, 0.0, 2.0),
+            Vec4::new(0.0, 0.0, 4.0, 3.0),
+            Vec4::new(0.0, 0.0, 0.0, 1.0)
+        );
+
+        assert_eq!(matrix.affine_inverse(), matrix.inverse());
+        assert_eq!(matrix * matrix.affine_inverse(), Matrix4::UNIT);
+
+        let displacement = Vec3::new(1.0, -2.0, 3.0).to_displacement_matrix();
+
+        assert_eq!(displacement.affine_inverse(), Vec3::new(-1.0, 2.0, -3.0).to_displacement_matrix());
+    }
+
+    // the depth after the perspective divide
+    fn depth(matrix: Matrix4<f64>, z: f64) -> f64 {
+        let clip = matrix * Vec4::new(0.0, 0.0, z, 1.0);
+        clip.z / clip.w
+    }
+
+    #[test]
+    fn perspective() {
+        let lh = Matrix4::persp_lh_matrix(90.0, 2.0, 1.0, 10.0);
+
+        assert_eq!(depth(lh, 1.0), -1.0);
+        assert_eq!(depth(lh, 10.0), 1.0);
+        // the corner of the near plane, tan(45) is not exactly 1
+        let corner = lh * Vec4::new(2.0, 1.0, 1.0, 1.0);
+        assert!(corner.distance(Vec4::new(1.0, 1.0, -1.0, 1.0)) < 1e-12, "{corner:?}");
+
+        let rh = Matrix4::persp_rh_matrix(90.0, 2.0, 1.0, 10.0);
+
+        assert_eq!(depth(rh, -1.0), -1.0);
+        assert_eq!(depth(rh, -10.0), 1.0);
+    }
+
+    #[test]
+    fn orthographic() {
+        let lh = Matrix4::ortho_lh_matrix(4.0, 2.0, 1.0, 3.0);
+
+        assert_eq!(lh * Vec4::new(2.0, -1.0, 1.0, 1.0), Vec4::new(1.0, -1.0, -1.0, 1.0));
+        assert_eq!(depth(lh, 3.0), 1.0);
+
+        let rh = Matrix4::ortho_rh_matrix(4.0, 2.0, 1.0, 3.0);
+
+        assert_eq!(depth(rh, -1.0), -1.0);
+        assert_eq!(depth(rh, -3.0), 1.0);
+    }
 }
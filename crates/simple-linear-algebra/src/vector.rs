@@ -1,6 +1,6 @@
-use std::ops::{Add, Mul, Sub};
+use std::ops::{Add, Div, Mul, Sub};
 
-use crate::num_traits::{One, Zero};
+use crate::num_traits::{One, Sqrt, Zero};
 
 pub mod vec2;
 pub mod vec3;
@@ -10,11 +10,22 @@ pub mod quaternion;
 pub trait Vector: Add<Output = Self>
     + Mul<Output = Self>
     + Sub<Output = Self>
+    + Copy
     + Sized
     + Zero
     + One
     + AxisUnits
 {
+    type Scalar: Copy
+        + Zero
+        + One
+        + Add<Output = Self::Scalar>
+        + Sub<Output = Self::Scalar>
+        + Mul<Output = Self::Scalar>
+        + Div<Output = Self::Scalar>
+        + PartialEq
+        + Sqrt;
+
     fn nullify(&mut self);
 
     fn normalize(&mut self);
@@ -24,6 +35,55 @@ pub trait Vector: Add<Output = Self>
     fn project(&mut self);
 
     fn to_projected(&self) -> Self;
+
+    fn dot(&self, rhs: Self) -> Self::Scalar;
+
+    fn length_squared(&self) -> Self::Scalar {
+        self.dot(*self)
+    }
+
+    fn length(&self) -> Self::Scalar {
+        self.length_squared().sqrt()
+    }
+
+    fn distance_squared(&self, rhs: Self) -> Self::Scalar {
+        (rhs - *self).length_squared()
+    }
+
+    fn distance(&self, rhs: Self) -> Self::Scalar {
+        (rhs - *self).length()
+    }
+
+    // t = 0 gives self, t = 1 gives rhs
+    fn lerp(&self, rhs: Self, t: Self::Scalar) -> Self
+    where
+        Self: Mul<Self::Scalar, Output = Self>
+    {
+        *self + (rhs - *self) * t
+    }
+
+    // the normal has to be normalized
+    fn reflect(&self, normal: Self) -> Self
+    where
+        Self: Mul<Self::Scalar, Output = Self>
+    {
+        let dot = self.dot(normal);
+
+        *self - normal * (dot + dot)
+    }
+
+    fn project_onto(&self, rhs: Self) -> Self
+    where
+        Self: Mul<Self::Scalar, Output = Self>
+    {
+        let len = rhs.length_squared();
+
+        if len == Self::Scalar::ZERO {
+            Self::ZERO
+        } else {
+            rhs * (self.dot(rhs) / len)
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
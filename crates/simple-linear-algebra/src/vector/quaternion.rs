@@ -1,6 +1,6 @@
 use std::ops::{Add, Div, Mul, Neg, Sub};
 
-use crate::{matrix::Unit, num_traits::{One, SinCos, Sqrt, Two, Zero}, vector::{AxisUnits, Vector, vec2::*, vec3::*, vec4::*}};
+use crate::{matrix::{Unit, matrix3::Matrix3}, num_traits::{One, SinCos, Sqrt, Two, Zero}, vector::{AxisUnits, Vector, vec2::*, vec3::*, vec4::*}};
 
 #[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
 pub struct Quaternion<T> {
@@ -62,6 +62,8 @@ impl<T: Copy
     + PartialEq
     + Sqrt
 > Vector for Quaternion<T> {
+    type Scalar = T;
+
     fn nullify(&mut self) {
         (self.x, self.y, self.z, self.w) =
             (T::ZERO, T::ZERO, T::ZERO, T::ZERO);
@@ -110,7 +112,7 @@ impl<T: Copy
     }
 
     fn to_projected(&self) -> Self {
-        if self.z == T::ZERO {
+        if self.w == T::ZERO {
             Self::ZERO
         } else {
             let x = self.x / self.w;
@@ -120,6 +122,10 @@ impl<T: Copy
             Self::new(x, y, z, T::ONE)
         }
     }
+
+    fn dot(&self, rhs: Self) -> T {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z + self.w * rhs.w
+    }
 }
 
 impl<T: Copy + Neg<Output = T>> Quaternion<T> {
@@ -135,6 +141,45 @@ impl<T: Copy + SinCos + Two + Div<Output = T> + Mul<Output = T>> Quaternion<T> {
     }
 }
 
+impl<T: Copy
+    + Zero
+    + One
+    + Two
+    + Add<Output = T>
+    + Sub<Output = T>
+    + Mul<Output = T>
+    + Div<Output = T>
+    + PartialOrd
+    + Sqrt
+> Quaternion<T> {
+    // The columns of the matrix have to be orthonormal and right-handed.
+    // The largest of the four components is found first to keep the division stable
+    pub fn from_rotation_matrix(matrix: Matrix3<T>) -> Self {
+        let Matrix3 { i, j, k } = matrix;
+
+        let four = T::TWO + T::TWO;
+        let trace = i.x + j.y + k.z;
+
+        if trace > T::ZERO {
+            let s = (trace + T::ONE).sqrt() * T::TWO;
+
+            Self::new((j.z - k.y) / s, (k.x - i.z) / s, (i.y - j.x) / s, s / four)
+        } else if i.x > j.y && i.x > k.z {
+            let s = (T::ONE + i.x - j.y - k.z).sqrt() * T::TWO;
+
+            Self::new(s / four, (j.x + i.y) / s, (k.x + i.z) / s, (j.z - k.y) / s)
+        } else if j.y > k.z {
+            let s = (T::ONE + j.y - i.x - k.z).sqrt() * T::TWO;
+
+            Self::new((j.x + i.y) / s, s / four, (k.y + j.z) / s, (k.x - i.z) / s)
+        } else {
+            let s = (T::ONE + k.z - i.x - j.y).sqrt() * T::TWO;
+
+            Self::new((k.x + i.z) / s, (k.y + j.z) / s, s / four, (i.y - j.x) / s)
+        }
+    }
+}
+
 impl<T: Copy + Add<Output = T> + Mul<Output = T> + Sub<Output = T> + Neg<Output = T>> Quaternion<T> {
     pub fn rotate(&mut self, quater: Quaternion<T>) {
         *self = self.to_rotated(quater);
@@ -225,6 +270,19 @@ impl<T: Copy + Mul<Output = T> + Sub<Output = T> + Add<Output = T>> Mul for Quat
     }
 }
 
+impl<T: Copy + Mul<Output = T>> Mul<T> for Quaternion<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        let x = self.x * rhs;
+        let y = self.y * rhs;
+        let z = self.z * rhs;
+        let w = self.w * rhs;
+
+        Self::new(x, y, z, w)
+    }
+}
+
 impl<T: Copy + Zero> From<Vec2<T>> for Quaternion<T> {
     fn from(value: Vec2<T>) -> Self {
         Self::new(value.x, value.y, T::ZERO, T::ZERO)
@@ -256,3 +314,36 @@ impl<T: Copy> Quaternion<T> {
         (*self).into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{matrix::matrix3::Matrix3, vector::{AxisUnits, Vector, quaternion::Quaternion, vec3::Vec3}};
+
+    fn assert_close(a: Vec3<f64>, b: Vec3<f64>) {
+        assert!(a.distance(b) < 1e-12, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn from_rotation_matrix() {
+        let rotations = [
+            Quaternion::from_angle(0.3, Vec3::new(1.0, 2.0, 3.0).to_normalized()),
+            Quaternion::from_angle(3.0, Vec3::X),
+            Quaternion::from_angle(3.0, Vec3::Y),
+            Quaternion::from_angle(3.0, Vec3::Z)
+        ];
+
+        for quater in rotations {
+            let matrix = Matrix3::new(
+                Vec3::X.to_raw_rotated(quater),
+                Vec3::Y.to_raw_rotated(quater),
+                Vec3::Z.to_raw_rotated(quater)
+            );
+
+            let restored = Quaternion::from_rotation_matrix(matrix);
+
+            for axis in [Vec3::X, Vec3::Y, Vec3::Z, Vec3::new(1.0, -2.0, 0.5)] {
+                assert_close(axis.to_raw_rotated(restored), axis.to_raw_rotated(quater));
+            }
+        }
+    }
+}
@@ -44,6 +44,8 @@ impl<T: Copy
     + PartialEq
     + Sqrt
 > Vector for Vec2<T> {
+    type Scalar = T;
+
     fn nullify(&mut self) {
         (self.x, self.y) = (T::ZERO, T::ZERO)
     }
@@ -83,6 +85,10 @@ impl<T: Copy
             Vec2::new(self.x / self.y, T::ONE)
         }
     }
+
+    fn dot(&self, rhs: Self) -> T {
+        self.x * rhs.x + self.y * rhs.y
+    }
 }
 
 impl<T: Copy + One + Zero> Vec2<T> {
@@ -103,6 +109,13 @@ impl<T: Copy + SinCos + Add<Output = T> + Mul<Output = T> + Neg<Output = T> + Ne
     }
 }
 
+impl<T: Copy + Mul<Output = T> + Sub<Output = T>> Vec2<T> {
+    // z component of the 3d cross product, positive when rhs is counter-clockwise from self
+    pub fn cross(&self, rhs: Self) -> T {
+        self.x * rhs.y - self.y * rhs.x
+    }
+}
+
 impl<T: Copy + Zero> Zero for Vec2<T> {
     const ZERO: Self = Vec2::splat(T::ZERO);
 }
@@ -214,3 +227,51 @@ impl<T: Copy + Zero> Vec2<T> {
 }
 
 impl_assign!(Vec2<T>);
+
+#[cfg(test)]
+mod tests {
+    use crate::vector::{Vector, vec2::Vec2};
+
+    #[test]
+    fn dot() {
+        assert_eq!(Vec2::new(1.0, 2.0).dot(Vec2::new(3.0, -4.0)), -5.0);
+    }
+
+    #[test]
+    fn cross() {
+        assert_eq!(Vec2::new(1.0, 0.0).cross(Vec2::new(0.0, 1.0)), 1.0);
+        assert_eq!(Vec2::new(0.0, 1.0).cross(Vec2::new(1.0, 0.0)), -1.0);
+    }
+
+    #[test]
+    fn length_and_distance() {
+        let vec = Vec2::new(3.0, 4.0);
+
+        assert_eq!(vec.length_squared(), 25.0);
+        assert_eq!(vec.length(), 5.0);
+        assert_eq!(Vec2::new(1.0, 1.0).distance(Vec2::new(4.0, 5.0)), 5.0);
+        assert_eq!(Vec2::new(1.0, 1.0).distance_squared(Vec2::new(4.0, 5.0)), 25.0);
+    }
+
+    #[test]
+    fn lerp() {
+        let (a, b) = (Vec2::new(0.0, 2.0), Vec2::new(4.0, -2.0));
+
+        assert_eq!(a.lerp(b, 0.0), a);
+        assert_eq!(a.lerp(b, 1.0), b);
+        assert_eq!(a.lerp(b, 0.25), Vec2::new(1.0, 1.0));
+    }
+
+    #[test]
+    fn reflect() {
+        let normal = Vec2::new(0.0, 1.0);
+
+        assert_eq!(Vec2::new(1.0, -1.0).reflect(normal), Vec2::new(1.0, 1.0));
+    }
+
+    #[test]
+    fn project_onto() {
+        assert_eq!(Vec2::new(2.0, 3.0).project_onto(Vec2::new(4.0, 0.0)), Vec2::new(2.0, 0.0));
+        assert_eq!(Vec2::new(2.0, 3.0).project_onto(Vec2::new(0.0, 0.0)), Vec2::new(0.0, 0.0));
+    }
+}
@@ -53,6 +53,8 @@ impl<T: Copy
     + PartialEq
     + Sqrt
 > Vector for Vec3<T> {
+    type Scalar = T;
+
     fn nullify(&mut self) {
         (self.x, self.y, self.z) = (T::ZERO, T::ZERO, T::ZERO);
     }
@@ -96,6 +98,10 @@ impl<T: Copy
             Vec3::new(x, y, T::ONE)
         }
     }
+
+    fn dot(&self, rhs: Self) -> T {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+    }
 }
 
 impl<T: Copy + One + Zero> Vec3<T> {
@@ -122,6 +128,16 @@ impl<T: Copy + Zero + Neg<Output = T> + Mul<Output = T> + Sub<Output = T> + Add<
     }
 }
 
+impl<T: Copy + Mul<Output = T> + Sub<Output = T>> Vec3<T> {
+    pub fn cross(&self, rhs: Self) -> Self {
+        Self::new(
+            self.y * rhs.z - self.z * rhs.y,
+            self.z * rhs.x - self.x * rhs.z,
+            self.x * rhs.y - self.y * rhs.x
+        )
+    }
+}
+
 impl<T: Copy + Zero> Zero for Vec3<T> {
     const ZERO: Self = Self::splat(T::ZERO);
 }
@@ -235,3 +251,51 @@ impl<T: Copy + Zero> Vec3<T> {
 }
 
 impl_assign!(Vec3<T>);
+
+#[cfg(test)]
+mod tests {
+    use crate::vector::{AxisUnits, Vector, vec3::Vec3};
+
+    #[test]
+    fn dot() {
+        assert_eq!(Vec3::new(1.0, 2.0, 3.0).dot(Vec3::new(4.0, -5.0, 6.0)), 12.0);
+    }
+
+    #[test]
+    fn cross() {
+        assert_eq!(Vec3::<f64>::X.cross(Vec3::Y), Vec3::Z);
+        assert_eq!(Vec3::<f64>::Y.cross(Vec3::X), -Vec3::Z);
+
+        let (a, b) = (Vec3::new(1.0, 2.0, 3.0), Vec3::new(-2.0, 0.5, 4.0));
+        let cross = a.cross(b);
+
+        assert_eq!(cross.dot(a), 0.0);
+        assert_eq!(cross.dot(b), 0.0);
+    }
+
+    #[test]
+    fn length_and_distance() {
+        let vec = Vec3::new(2.0, 3.0, 6.0);
+
+        assert_eq!(vec.length_squared(), 49.0);
+        assert_eq!(vec.length(), 7.0);
+        assert_eq!(Vec3::new(1.0, 1.0, 1.0).distance(Vec3::new(3.0, 4.0, 7.0)), 7.0);
+    }
+
+    #[test]
+    fn lerp() {
+        let (a, b) = (Vec3::new(0.0, 2.0, 4.0), Vec3::new(4.0, -2.0, 0.0));
+
+        assert_eq!(a.lerp(b, 0.5), Vec3::new(2.0, 0.0, 2.0));
+    }
+
+    #[test]
+    fn reflect() {
+        assert_eq!(Vec3::new(1.0, -2.0, 3.0).reflect(Vec3::Y), Vec3::new(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn project_onto() {
+        assert_eq!(Vec3::new(2.0, 3.0, 4.0).project_onto(Vec3::Z * 2.0), Vec3::new(0.0, 0.0, 4.0));
+    }
+}
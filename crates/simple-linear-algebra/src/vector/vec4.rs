@@ -62,6 +62,8 @@ impl<T: Copy
     + PartialEq
     + Sqrt
 > Vector for Vec4<T> {
+    type Scalar = T;
+
     fn nullify(&mut self) {
         (self.x, self.y, self.z, self.w) =
             (T::ZERO, T::ZERO, T::ZERO, T::ZERO);
@@ -110,7 +112,7 @@ impl<T: Copy
     }
 
     fn to_projected(&self) -> Self {
-        if self.z == T::ZERO {
+        if self.w == T::ZERO {
             Self::ZERO
         } else {
             let x = self.x / self.w;
@@ -119,6 +121,10 @@ impl<T: Copy
             Self::new(x, y, z, T::ONE)
         }
     }
+
+    fn dot(&self, rhs: Self) -> T {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z + self.w * rhs.w
+    }
 }
 
 impl<T: Copy + Zero + One> Vec4<T> {
@@ -243,3 +249,43 @@ impl<T: Copy> Vec4<T> {
 }
 
 impl_assign!(Vec4<T>);
+
+#[cfg(test)]
+mod tests {
+    use crate::vector::{Vector, vec4::Vec4};
+
+    #[test]
+    fn dot() {
+        assert_eq!(Vec4::new(1.0, 2.0, 3.0, 4.0).dot(Vec4::new(4.0, -3.0, 2.0, -1.0)), 0.0);
+    }
+
+    #[test]
+    fn length_and_distance() {
+        let vec = Vec4::new(1.0, 1.0, 1.0, 1.0);
+
+        assert_eq!(vec.length_squared(), 4.0);
+        assert_eq!(vec.length(), 2.0);
+        assert_eq!(Vec4::new(0.0, 0.0, 0.0, 0.0).distance(Vec4::new(2.0, 4.0, 4.0, 1.0) - Vec4::new(0.0, 0.0, 0.0, 1.0)), 6.0);
+    }
+
+    #[test]
+    fn lerp() {
+        let (a, b) = (Vec4::new(0.0, 0.0, 0.0, 1.0), Vec4::new(2.0, 4.0, 6.0, 1.0));
+
+        assert_eq!(a.lerp(b, 0.5), Vec4::new(1.0, 2.0, 3.0, 1.0));
+    }
+
+    #[test]
+    fn reflect() {
+        let normal = Vec4::new(0.0, 0.0, 0.0, 1.0);
+
+        assert_eq!(Vec4::new(1.0, 2.0, 3.0, -4.0).reflect(normal), Vec4::new(1.0, 2.0, 3.0, 4.0));
+    }
+
+    #[test]
+    fn project_onto() {
+        let onto = Vec4::new(1.0, 1.0, 0.0, 0.0);
+
+        assert_eq!(Vec4::new(3.0, 1.0, 5.0, 7.0).project_onto(onto), Vec4::new(2.0, 2.0, 0.0, 0.0));
+    }
+}
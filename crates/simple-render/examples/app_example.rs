@@ -1,15 +1,16 @@
-use std::{fs::File, path::Path, sync::{Arc, Mutex}, thread};
+use std::{fs::File, path::Path, sync::{Arc, Mutex}};
 
-use minifb::{Key, Window, WindowOptions};
+use minifb::WindowOptions;
 use simple_linear_algebra::vector::{vec2::Vec2, vec3::Vec3};
-use simple_render::{color::Color, render::{Render, app_handler::{AppHandler, Event}, image::Image, buffer::BufferSize, wait}};
+use simple_render::{color::Color, render::{Render, app_handler::{AppHandler, Event}, backend::minifb::MinifbBackend, image::Image, buffer::BufferSize, input::Key}};
 
-struct App(Color, bool, Image);
+// colour, need to redraw, image, seconds since the last colour step
+struct App(Color, bool, Image, f64);
 
 impl AppHandler for App {
     fn event(&mut self, event: Event) {
         match event {
-            Event::KeyPressed { key } => {
+            Event::KeyPressed { key, .. } => {
                 self.1 = true;
                 match key {
                     Key::W => {
@@ -34,7 +35,24 @@ impl AppHandler for App {
                 let triangle = Vec3::new(Vec2::new(0, 0), Vec2::new(100, 400), Vec2::new(700, 200));
 
                 buffer.fill_triangle(triangle, Color::GREEN);
+
+                let overlay = Vec2::new(Vec2::new(50, 50), Vec2::new(450, 450));
+
+                buffer.fill_rectangle(overlay, Color::from_rgba(255, 0, 0, 128));
             }
+
+            _ => ()
+        }
+    }
+
+    fn update(&mut self, dt: f64) {
+        self.3 += dt;
+
+        while self.3 >= 0.1 {
+            self.3 -= 0.1;
+
+            self.0.0 += 1;
+            self.1 = true;
         }
     }
 
@@ -56,29 +74,15 @@ fn main() {
 
     let image = Image::from_png(file).unwrap();
 
-    let app = App(Color::from_rgb(0, 255, 255), false, image);
+    let app = App(Color::from_rgb(0, 255, 255), false, image, 0.0);
 
-    let mut options = WindowOptions::default();
-    options.resize = true;
+    let options = WindowOptions { resize: true, ..WindowOptions::default() };
 
-    let window = Window::new("Test", size.width, size.height, options).unwrap();
+    let backend = MinifbBackend::open("Test", size, options).unwrap();
 
     let mut_app = Arc::new(Mutex::new(app));
-    let clone = mut_app.clone();
-
-    let mut render = Render::new(mut_app, 60.0, window);
 
-    let _handle = thread::spawn(move || {
-        loop {
-            clone.lock().unwrap()
-                .1 = true;
-
-            clone.lock().unwrap()
-                .0.0 += 1;
-
-            wait(0.1);
-        }
-    });
+    let mut render = Render::new(mut_app, 60.0, backend);
 
     render.run();
 }
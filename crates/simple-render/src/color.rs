@@ -1,45 +1,80 @@
-use std::{ops::{Add, AddAssign, Div, Mul, MulAssign, Sub, SubAssign}, u8, u32};
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Sub, SubAssign};
 
-#[derive(Clone, Copy)]
+// packed as 0xAARRGGBB
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Color(pub u32);
 
 impl Color {
-    pub const fn new(color: u32) -> Self {
-        Self(color)
+    // 0xRRGGBB, always opaque
+    pub const fn new(rgb: u32) -> Self {
+        Self(rgb | 0xFF00_0000)
+    }
+
+    // 0xAARRGGBB, the alpha is kept as it is
+    pub const fn from_argb_u32(argb: u32) -> Self {
+        Self(argb)
     }
 
     pub const fn from_rgb(r: u8, g: u8, b: u8) -> Self {
-        let (r, g, b) = (r as u32, g as u32, b as u32);
-        let color = (r << 16) | (g << 8) | b;
-        Self::new(color)
+        Self::from_argb(u8::MAX, r, g, b)
+    }
+
+    pub const fn from_argb(a: u8, r: u8, g: u8, b: u8) -> Self {
+        let (a, r, g, b) = (a as u32, r as u32, g as u32, b as u32);
+        let color = (a << 24) | (r << 16) | (g << 8) | b;
+        Self::from_argb_u32(color)
+    }
+
+    pub const fn from_rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self::from_argb(a, r, g, b)
     }
 
     pub const fn to_rgb(&self) -> (u8, u8, u8) {
         ((self.0 >> 16) as u8, (self.0 >> 8) as u8, self.0 as u8)
     }
 
-    pub const BLACK: Self = Color::new(0);
+    pub const fn to_argb(&self) -> (u8, u8, u8, u8) {
+        let (r, g, b) = self.to_rgb();
+        (self.alpha(), r, g, b)
+    }
+
+    pub const fn alpha(&self) -> u8 {
+        (self.0 >> 24) as u8
+    }
+
+    pub const TRANSPARENT: Self = Color::from_argb_u32(0);
+    pub const BLACK: Self = Color::from_rgb(0, 0, 0);
     pub const WHITE: Self = Color::new(u32::MAX);
     pub const RED: Self = Color::from_rgb(u8::MAX, 0, 0);
     pub const GREEN: Self = Color::from_rgb(0, u8::MAX, 0);
     pub const BLUE: Self = Color::from_rgb(0, 0, u8::MAX);
 
+    pub const fn a(&self, a: u8) -> Self {
+        let (r, g, b) = self.to_rgb();
+
+        Self::from_argb(a, r, g, b)
+    }
+
     pub const fn r(&self, r: u8) -> Self {
-        let (_, g, b) = self.to_rgb();
+        let (a, _, g, b) = self.to_argb();
 
-        Self::from_rgb(r, g, b)
+        Self::from_argb(a, r, g, b)
     }
 
     pub const fn g(&self, g: u8) -> Self {
-        let (r, _, b) = self.to_rgb();
+        let (a, r, _, b) = self.to_argb();
 
-        Self::from_rgb(r, g, b)
+        Self::from_argb(a, r, g, b)
     }
 
     pub const fn b(&self, b: u8) -> Self {
-        let (r, g, _) = self.to_rgb();
+        let (a, r, g, _) = self.to_argb();
+
+        Self::from_argb(a, r, g, b)
+    }
 
-        Self::from_rgb(r, g, b)
+    pub const fn mut_a(&mut self, a: u8) {
+        *self = self.a(a);
     }
 
     pub const fn mut_r(&mut self, r: u8) {
@@ -53,6 +88,73 @@ impl Color {
     pub const fn mut_b(&mut self, b: u8) {
         *self = self.b(b);
     }
+
+    pub const fn is_opaque(&self) -> bool {
+        self.alpha() == u8::MAX
+    }
+
+    // scales the alpha channel, e.g. by the pixel coverage of an anti-aliased edge
+    pub const fn fade(&self, factor: u8) -> Self {
+        let a = (self.alpha() as u16 * factor as u16) / 255;
+
+        self.a(a as u8)
+    }
+
+    // t = 0 gives self, t = 255 gives other, alpha is interpolated as well
+    pub const fn lerp(&self, other: Color, t: u8) -> Self {
+        let (a1, r1, g1, b1) = self.to_argb();
+        let (a2, r2, g2, b2) = other.to_argb();
+
+        const fn mix(x: u8, y: u8, t: u8) -> u8 {
+            let (x, y, t) = (x as u16, y as u16, t as u16);
+            ((x * (255 - t) + y * t) / 255) as u8
+        }
+
+        Self::from_argb(mix(a1, a2, t), mix(r1, r2, t), mix(g1, g2, t), mix(b1, b2, t))
+    }
+
+    // self is the source, dst is the pixel already in the buffer
+    pub fn blend(self, dst: Color, mode: BlendMode) -> Self {
+        let src = self.a(u8::MAX);
+
+        let blended = match mode {
+            BlendMode::Replace => return self,
+            BlendMode::SourceOver => src,
+            BlendMode::Additive => dst + src,
+            BlendMode::Multiply => dst * src,
+            BlendMode::Screen => src + (dst - src * dst),
+        };
+
+        let (src_a, dst_a) = (self.alpha() as u32, dst.alpha() as u32);
+
+        // non-premultiplied source-over: out_a = a + dst_a * (1 - a)
+        let dst_weight = dst_a * (255 - src_a) / 255;
+        let out_a = src_a + dst_weight;
+
+        if out_a == 0 {
+            return Color::TRANSPARENT;
+        }
+
+        let (_, r1, g1, b1) = blended.to_argb();
+        let (_, r2, g2, b2) = dst.to_argb();
+
+        let mix = |x: u8, y: u8| -> u8 {
+            ((x as u32 * src_a + y as u32 * dst_weight) / out_a) as u8
+        };
+
+        Self::from_argb(out_a as u8, mix(r1, r2), mix(g1, g2), mix(b1, b2))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlendMode {
+    // overwrites the pixel, alpha included
+    Replace,
+    #[default]
+    SourceOver,
+    Additive,
+    Multiply,
+    Screen,
 }
 
 impl Add for Color {
@@ -66,7 +168,7 @@ impl Add for Color {
         let g = g1.saturating_add(g2);
         let b = b1.saturating_add(b2);
 
-        Self::from_rgb(r, g, b)
+        Self::from_argb(self.alpha(), r, g, b)
     }
 }
 
@@ -87,7 +189,7 @@ impl Sub for Color {
         let g = g1.saturating_sub(g2);
         let b = b1.saturating_sub(b2);
 
-        Self::from_rgb(r, g, b)
+        Self::from_argb(self.alpha(), r, g, b)
     }
 }
 
@@ -111,7 +213,7 @@ impl Mul for Color {
         let g = (g1 * g2) / 255;
         let b = (b1 * b2) / 255;
 
-        Self::from_rgb(r as u8, g as u8, b as u8)
+        Self::from_argb(self.alpha(), r as u8, g as u8, b as u8)
     }
 }
 
@@ -140,6 +242,75 @@ impl Div for Color {
         let g = div(g1, g2);
         let b = div(b1, b2);
 
-        Self::from_rgb(r, g, b)
+        Self::from_argb(self.alpha(), r, g, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::color::{BlendMode, Color};
+
+    #[test]
+    fn packing() {
+        let color = Color::from_argb_u32(0x80FF_2010);
+
+        assert_eq!(color.to_argb(), (0x80, 0xFF, 0x20, 0x10));
+        assert_eq!(Color::from_rgba(0xFF, 0x20, 0x10, 0x80), color);
+        assert_eq!(Color::from_argb(0x80, 0xFF, 0x20, 0x10), color);
+
+        // without an alpha the colour is opaque
+        assert_eq!(Color::new(0x12_3456), Color(0xFF12_3456));
+        assert_eq!(Color::from_rgb(0x12, 0x34, 0x56), Color(0xFF12_3456));
+    }
+
+    #[test]
+    fn fade_and_lerp() {
+        let color = Color::from_argb(200, 10, 20, 30);
+
+        assert_eq!(color.fade(128), Color::from_argb(100, 10, 20, 30));
+        assert_eq!(color.fade(255), color);
+        assert_eq!(color.fade(0), Color::from_argb(0, 10, 20, 30));
+
+        let (from, to) = (Color::TRANSPARENT, Color::from_argb(255, 255, 100, 50));
+
+        assert_eq!(from.lerp(to, 0), from);
+        assert_eq!(from.lerp(to, 255), to);
+        assert_eq!(from.lerp(to, 128), Color::from_argb(128, 128, 50, 25));
+        assert_eq!(Color::from_rgb(200, 0, 0).lerp(Color::from_rgb(100, 0, 0), 51), Color::from_rgb(180, 0, 0));
+    }
+
+    #[test]
+    fn replace() {
+        let src = Color::from_argb(0x40, 10, 20, 30);
+
+        assert_eq!(src.blend(Color::WHITE, BlendMode::Replace), src);
+        assert_eq!(Color::TRANSPARENT.blend(Color::WHITE, BlendMode::Replace), Color::TRANSPARENT);
+    }
+
+    #[test]
+    fn source_over() {
+        let over = |src: Color, dst: Color| src.blend(dst, BlendMode::SourceOver);
+
+        assert_eq!(over(Color::RED, Color::BLUE), Color::RED);
+        assert_eq!(over(Color::RED.a(128), Color::BLUE), Color::from_argb(255, 128, 0, 127));
+        assert_eq!(over(Color::RED.a(0), Color::BLUE), Color::BLUE);
+
+        // onto a transparent pixel the source is kept as it is
+        assert_eq!(over(Color::RED.a(128), Color::TRANSPARENT), Color::RED.a(128));
+        assert_eq!(over(Color::RED.a(0), Color::TRANSPARENT), Color::TRANSPARENT);
+    }
+
+    #[test]
+    fn colour_modes() {
+        let dst = Color::from_rgb(100, 100, 100);
+
+        // saturates instead of wrapping
+        assert_eq!(Color::from_rgb(200, 100, 10).blend(dst, BlendMode::Additive), Color::from_rgb(255, 200, 110));
+        assert_eq!(Color::from_rgb(128, 255, 0).blend(Color::from_rgb(200, 100, 50), BlendMode::Multiply), Color::from_rgb(100, 100, 0));
+        assert_eq!(Color::from_rgb(128, 0, 255).blend(dst, BlendMode::Screen), Color::from_rgb(178, 100, 255));
+
+        // the alpha mixes the result with the pixel like in source-over
+        assert_eq!(Color::from_argb(0, 255, 255, 255).blend(dst, BlendMode::Additive), dst);
+        assert_eq!(Color::from_argb(128, 200, 100, 10).blend(dst, BlendMode::Additive), Color::from_rgb(177, 150, 105));
     }
 }
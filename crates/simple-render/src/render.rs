@@ -1,32 +1,89 @@
-use std::{sync::{Arc, Mutex}, thread, time::Duration};
+use std::{path::PathBuf, sync::{Arc, Mutex}, thread, time::{Duration, Instant}};
 
-use minifb::Window;
-
-use crate::{color::Color, render::{app_handler::{AppHandler, Event}, buffer::{Buffer, BufferSize}}};
+use crate::{color::Color, render::{app_handler::{AppHandler, Event}, backend::Backend, buffer::Buffer, input::{InputState, Key}, stats::{FrameStats, FrameTimings}}};
 
 pub mod buffer;
 pub mod app_handler;
 pub mod image;
+pub mod headless;
+pub mod backend;
+pub mod input;
+pub mod stats;
+pub mod font;
+pub mod raster;
+pub mod stroke;
+pub mod shape;
+pub mod path;
+
+// longest frame the update loop catches up on, slower frames make the app slow down instead of stalling
+const MAX_FRAME_TIME: f64 = 0.25;
 
 pub fn wait(secs: f64) {
     thread::sleep(Duration::from_secs_f64(secs));
 }
 
-pub struct Render<T> {
+pub struct Render<T, B> {
     app: Arc<Mutex<T>>,
     fps: f64,
-    window: Window,
+    // fixed `AppHandler::update` steps per second
+    update_rate: f64,
+    backend: B,
+    screenshot_key: Option<(Key, PathBuf)>,
+    stats: Arc<Mutex<FrameStats>>,
+    overlay: bool,
+    overlay_key: Option<Key>
 }
 
-impl<'a, T: AppHandler + Send + Sync> Render<T> {
-    pub const fn new(app: Arc<Mutex<T>>, fps: f64, window: Window) -> Self {
-        Self { app, fps, window }
+impl<T: AppHandler + Send + Sync, B: Backend> Render<T, B> {
+    pub fn new(app: Arc<Mutex<T>>, fps: f64, backend: B) -> Self {
+        Self {
+            app,
+            fps,
+            update_rate: fps,
+            backend,
+            screenshot_key: None,
+            stats: Arc::new(Mutex::new(FrameStats::default())),
+            overlay: false,
+            overlay_key: None
+        }
+    }
+
+    // by default the app is updated at the frame rate
+    pub fn set_update_rate(&mut self, update_rate: f64) {
+        self.update_rate = update_rate;
+    }
+
+    pub fn set_screenshot_key<P: Into<PathBuf>>(&mut self, key: Key, path: P) {
+        self.screenshot_key = Some((key, path.into()));
+    }
+
+    // shared with the render loop, so it can be read while `run` is going
+    pub fn stats(&self) -> Arc<Mutex<FrameStats>> {
+        self.stats.clone()
+    }
+
+    // The overlay makes the app redraw every frame, as the graphs change all the time
+    pub fn set_overlay(&mut self, overlay: bool) {
+        self.overlay = overlay;
+    }
+
+    pub fn set_overlay_key(&mut self, key: Key) {
+        self.overlay_key = Some(key);
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    pub fn mut_backend(&mut self) -> &mut B {
+        &mut self.backend
     }
 
     pub fn run(&mut self) {
-        let tick = 1.0 / self.fps;
+        let frame_time = 1.0 / self.fps;
+        let step = 1.0 / self.update_rate;
 
-        let size = BufferSize::from_get_size(self.window.get_size());
+        let size = self.backend.size();
 
 
         let mut front = (Buffer::init(size), true);
@@ -35,12 +92,48 @@ impl<'a, T: AppHandler + Send + Sync> Render<T> {
         self.app.lock().unwrap()
             .event(Event::RedrawReqiest { buffer: &mut front.0 } );
 
-        while self.window.is_open() {
-            let keys = self.window.get_keys();
-            let r_size = BufferSize::from_get_size(self.window.get_size());
+        let mut input = InputState::default();
+        let mut last_size = size;
+
+        let mut accumulator = 0.0;
+        let mut last_frame = Instant::now();
+
+        while self.backend.is_open() {
+            let frame_start = Instant::now();
+
+            accumulator += (frame_start - last_frame).as_secs_f64().min(MAX_FRAME_TIME);
+            last_frame = frame_start;
+
+            let events = input.poll(&self.backend);
+            let r_size = self.backend.size();
+
+            let resized = r_size != last_size;
+            last_size = r_size;
+
+            let key_screenshot = self.screenshot_key.as_ref()
+                .filter(|(screenshot_key, _)| events.iter().any(|event| matches!(
+                    event,
+                    Event::KeyPressed { key, repeat: false } if key == screenshot_key
+                )))
+                .map(|(_, path)| path.clone());
+
+            let toggle_overlay = self.overlay_key.is_some_and(|overlay_key| events.iter().any(|event| matches!(
+                event,
+                Event::KeyPressed { key, repeat: false } if *key == overlay_key
+            )));
+
+            self.overlay ^= toggle_overlay;
+
+            let overlay = self.overlay;
+            let stats = &self.stats;
+
+            let mut timings = FrameTimings::default();
 
             thread::scope(|s| {
-                s.spawn(|| {
+                let app_thread = s.spawn(|| {
+                    let mut timings = FrameTimings::default();
+                    let mut start = Instant::now();
+
                     let is_resized = back.0.size != r_size;
 
                     if is_resized {
@@ -53,34 +146,184 @@ impl<'a, T: AppHandler + Send + Sync> Render<T> {
                         back.0.size = r_size;
                     }
 
-                    for key in keys {
+                    if resized {
+                        self.app.lock().unwrap()
+                            .event(Event::Resized { size: r_size });
+                    }
+
+                    for event in events {
+                        self.app.lock().unwrap()
+                            .event(event);
+                    }
+
+                    timings.events = start.elapsed().as_secs_f64();
+                    start = Instant::now();
+
+                    while accumulator >= step {
                         self.app.lock().unwrap()
-                            .event(Event::KeyPressed { key });
+                            .update(step);
+
+                        accumulator -= step;
                     }
 
+                    timings.update = start.elapsed().as_secs_f64();
+                    start = Instant::now();
+
                     back.1 = if self.app.lock().unwrap().need_to_redraw() {
                         self.app.lock().unwrap().redrawed();
                         true
                     } else {
-                        is_resized
+                        // the frame the overlay is turned off has to be drawn without it
+                        is_resized || overlay || toggle_overlay
                     };
 
                     if back.1 {
                         self.app.lock().unwrap()
                             .event(Event::RedrawReqiest { buffer: &mut back.0 });
                     }
+
+                    if overlay {
+                        stats.lock().unwrap()
+                            .draw_overlay(&mut back.0, frame_time);
+                    }
+
+                    timings.redraw = start.elapsed().as_secs_f64();
+
+                    timings
                 });
 
+                let start = Instant::now();
+
                 if front.1 {
-                    self.window.update_with_buffer(&front.0.raw_buffer.0, front.0.size.width, front.0.size.height).unwrap();
+                    self.backend.present(&front.0);
                 } else {
-                    self.window.update();
+                    self.backend.update();
                 }
 
-                wait(tick);
+                let present = start.elapsed().as_secs_f64();
+
+                timings = app_thread.join().unwrap();
+                timings.present = present;
             });
 
-            std::mem::swap(&mut front, &mut back);
+            {
+                let mut app = self.app.lock().unwrap();
+
+                let app_screenshot = app.screenshot();
+
+                for path in key_screenshot.into_iter().chain(app_screenshot) {
+                    if let Err(error) = front.0.save_png(&path) {
+                        app.screenshot_failed(path, error);
+                    }
+                }
+            }
+
+            // a buffer that wasn't redrawn is left behind, so the front one stays the one on screen
+            if back.1 {
+                std::mem::swap(&mut front, &mut back);
+            } else {
+                front.1 = false;
+            }
+
+            // only the rest of the frame is slept, the time spent on it already counts
+            let elapsed = frame_start.elapsed().as_secs_f64();
+
+            if elapsed < frame_time {
+                wait(frame_time - elapsed);
+            }
+
+            timings.frame = frame_start.elapsed().as_secs_f64();
+
+            self.stats.lock().unwrap()
+                .push(timings);
+        }
+
+        self.app.lock().unwrap()
+            .event(Event::CloseRequested);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::{self, File}, path::PathBuf, sync::{Arc, Mutex}};
+
+    use png::EncodingError;
+
+    use crate::{color::Color, render::{Render, app_handler::{AppHandler, Event}, backend::fake::FakeBackend, image::Image}};
+
+    // draws red once and asks for a screenshot every frame
+    struct Screenshots {
+        dir: PathBuf,
+        taken: Vec<PathBuf>,
+        failed: Vec<PathBuf>
+    }
+
+    impl Screenshots {
+        fn new(dir: PathBuf) -> Arc<Mutex<Self>> {
+            Arc::new(Mutex::new(Self { dir, taken: Vec::new(), failed: Vec::new() }))
+        }
+    }
+
+    impl AppHandler for Screenshots {
+        fn event(&mut self, event: Event) {
+            if let Event::RedrawReqiest { buffer } = event {
+                buffer.fill(Color::RED);
+            }
+        }
+
+        fn need_to_redraw(&self) -> bool {
+            false
+        }
+
+        fn redrawed(&mut self) {}
+
+        fn screenshot(&mut self) -> Option<PathBuf> {
+            let path = self.dir.join(format!("{}.png", self.taken.len()));
+            self.taken.push(path.clone());
+
+            Some(path)
+        }
+
+        fn screenshot_failed(&mut self, path: PathBuf, _error: EncodingError) {
+            self.failed.push(path);
+        }
+    }
+
+    #[test]
+    fn failed_screenshot() {
+        let app = Screenshots::new(std::env::temp_dir().join("simple-render-missing-directory"));
+
+        let mut render = Render::new(app.clone(), 1000.0, FakeBackend::new(2));
+        render.run();
+
+        let app = app.lock().unwrap();
+
+        assert_eq!(app.failed.len(), 2);
+        assert_eq!(app.failed, app.taken);
+    }
+
+    #[test]
+    fn screenshot_without_redraw() {
+        let dir = std::env::temp_dir().join(format!("simple-render-screenshots-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let app = Screenshots::new(dir.clone());
+
+        let mut render = Render::new(app.clone(), 1000.0, FakeBackend::new(3));
+        render.run();
+
+        let app = app.lock().unwrap();
+
+        assert!(app.failed.is_empty());
+        assert_eq!(app.taken.len(), 3);
+
+        // only the first frame is presented, the later ones show the same buffer
+        for path in &app.taken {
+            let image = Image::from_png(File::open(path).unwrap()).unwrap();
+
+            assert!(image.0.raw_buffer.0.iter().all(|&pixel| pixel == Color::RED.0), "{path:?}");
         }
+
+        fs::remove_dir_all(dir).unwrap();
     }
 }
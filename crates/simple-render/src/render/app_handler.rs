@@ -1,16 +1,42 @@
-use minifb::Key;
+use std::path::PathBuf;
 
-use crate::render::buffer::Buffer;
+use png::EncodingError;
+use simple_linear_algebra::vector::vec2::Vec2;
+
+use crate::render::{buffer::{Buffer, BufferSize}, input::{Key, MouseButton}};
 
 pub trait AppHandler {
     fn event(&mut self, event: Event);
 
+    // called with a fixed time step in seconds, possibly several times per frame to catch up
+    fn update(&mut self, _dt: f64) {}
+
     fn need_to_redraw(&self) -> bool;
 
     fn redrawed(&mut self);
+
+    // polled once per frame, a returned path makes `Render` save the front buffer there
+    fn screenshot(&mut self) -> Option<PathBuf> {
+        None
+    }
+
+    // a screenshot that could not be saved, from the screenshot key or `screenshot`
+    fn screenshot_failed(&mut self, _path: PathBuf, _error: EncodingError) {}
 }
 
+// Mouse positions are in buffer pixels from the top left corner
 pub enum Event<'a> {
-    KeyPressed { key: Key },
+    // sent every frame while the key is held, `repeat` is false the first time
+    KeyPressed { key: Key, repeat: bool },
+    KeyReleased { key: Key },
+    MouseMoved { pos: Vec2<f64> },
+    // outside the window the last known position is used, None if the cursor was never inside
+    MouseButtonPressed { button: MouseButton, pos: Option<Vec2<f64>> },
+    MouseButtonReleased { button: MouseButton, pos: Option<Vec2<f64>> },
+    // positive y scrolls up
+    MouseWheel { delta: Vec2<f64> },
+    Resized { size: BufferSize },
+    // the window is closing, no more events follow
+    CloseRequested,
     RedrawReqiest { buffer: &'a mut Buffer }
 }
@@ -0,0 +1,37 @@
+use simple_linear_algebra::vector::vec2::Vec2;
+
+use crate::render::{buffer::{Buffer, BufferSize}, input::{Key, MouseButton}};
+
+#[cfg(feature = "minifb")]
+pub mod minifb;
+#[cfg(test)]
+pub mod fake;
+
+pub trait Backend {
+    fn is_open(&self) -> bool;
+
+    fn size(&self) -> BufferSize;
+
+    // currently held keys
+    fn keys(&self) -> Vec<Key>;
+
+    // in buffer pixels, None when the cursor is outside the window
+    fn mouse_pos(&self) -> Option<Vec2<f64>> {
+        None
+    }
+
+    // currently held buttons
+    fn mouse_buttons(&self) -> Vec<MouseButton> {
+        Vec::new()
+    }
+
+    // scroll since the last frame, None if there was none
+    fn scroll_wheel(&self) -> Option<Vec2<f64>> {
+        None
+    }
+
+    fn present(&mut self, buffer: &Buffer);
+
+    // keeps the backend alive when there is nothing new to present
+    fn update(&mut self);
+}
@@ -0,0 +1,58 @@
+use simple_linear_algebra::vector::vec2::Vec2;
+
+use crate::render::{backend::Backend, buffer::{Buffer, BufferSize}, input::{Key, MouseButton}};
+
+// test backend, open for a fixed number of frames
+pub struct FakeBackend {
+    pub frames: usize,
+    pub size: BufferSize,
+    pub pos: Option<Vec2<f64>>,
+    pub buttons: Vec<MouseButton>
+}
+
+impl FakeBackend {
+    pub const fn new(frames: usize) -> Self {
+        Self { frames, size: BufferSize::new(4, 4), pos: None, buttons: Vec::new() }
+    }
+
+    pub const fn with_size(mut self, size: BufferSize) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_mouse(mut self, pos: Option<Vec2<f64>>, buttons: Vec<MouseButton>) -> Self {
+        self.pos = pos;
+        self.buttons = buttons;
+        self
+    }
+}
+
+impl Backend for FakeBackend {
+    fn is_open(&self) -> bool {
+        self.frames > 0
+    }
+
+    fn size(&self) -> BufferSize {
+        self.size
+    }
+
+    fn keys(&self) -> Vec<Key> {
+        Vec::new()
+    }
+
+    fn mouse_pos(&self) -> Option<Vec2<f64>> {
+        self.pos
+    }
+
+    fn mouse_buttons(&self) -> Vec<MouseButton> {
+        self.buttons.clone()
+    }
+
+    fn present(&mut self, _buffer: &Buffer) {
+        self.frames = self.frames.saturating_sub(1);
+    }
+
+    fn update(&mut self) {
+        self.frames = self.frames.saturating_sub(1);
+    }
+}
@@ -0,0 +1,116 @@
+use minifb::{MouseMode, Window, WindowOptions};
+use simple_linear_algebra::vector::vec2::Vec2;
+
+use crate::render::{backend::Backend, buffer::{Buffer, BufferSize}, input::{Key, MouseButton}};
+
+pub struct MinifbBackend {
+    window: Window
+}
+
+impl MinifbBackend {
+    pub const fn new(window: Window) -> Self {
+        Self { window }
+    }
+
+    pub fn open(title: &str, size: BufferSize, options: WindowOptions) -> Result<Self, minifb::Error> {
+        let window = Window::new(title, size.width, size.height, options)?;
+
+        Ok(Self::new(window))
+    }
+
+    pub fn window(&self) -> &Window {
+        &self.window
+    }
+
+    pub fn mut_window(&mut self) -> &mut Window {
+        &mut self.window
+    }
+}
+
+impl From<Window> for MinifbBackend {
+    fn from(value: Window) -> Self {
+        Self::new(value)
+    }
+}
+
+impl Backend for MinifbBackend {
+    fn is_open(&self) -> bool {
+        self.window.is_open()
+    }
+
+    fn size(&self) -> BufferSize {
+        BufferSize::from_get_size(self.window.get_size())
+    }
+
+    fn keys(&self) -> Vec<Key> {
+        self.window.get_keys()
+            .into_iter()
+            .map(Key::from)
+            .collect()
+    }
+
+    fn mouse_pos(&self) -> Option<Vec2<f64>> {
+        self.window.get_mouse_pos(MouseMode::Discard)
+            .map(|(x, y)| Vec2::new(x as f64, y as f64))
+    }
+
+    fn mouse_buttons(&self) -> Vec<MouseButton> {
+        MouseButton::ALL
+            .into_iter()
+            .filter(|&button| self.window.get_mouse_down(button.into()))
+            .collect()
+    }
+
+    fn scroll_wheel(&self) -> Option<Vec2<f64>> {
+        self.window.get_scroll_wheel()
+            .map(|(x, y)| Vec2::new(x as f64, y as f64))
+    }
+
+    fn present(&mut self, buffer: &Buffer) {
+        self.window.update_with_buffer(&buffer.raw_buffer.0, buffer.size.width, buffer.size.height).unwrap();
+    }
+
+    fn update(&mut self) {
+        self.window.update();
+    }
+}
+
+impl From<MouseButton> for minifb::MouseButton {
+    fn from(value: MouseButton) -> Self {
+        match value {
+            MouseButton::Left => minifb::MouseButton::Left,
+            MouseButton::Middle => minifb::MouseButton::Middle,
+            MouseButton::Right => minifb::MouseButton::Right
+        }
+    }
+}
+
+macro_rules! impl_from_minifb_key {
+    ($($k:ident),*) => {
+        impl From<minifb::Key> for Key {
+            fn from(value: minifb::Key) -> Self {
+                match value {
+                    $(minifb::Key::$k => Key::$k,)*
+                    _ => Key::Unknown
+                }
+            }
+        }
+    };
+}
+
+impl_from_minifb_key!(
+    Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9,
+    A, B, C, D, E, F, G, H, I, J, K, L, M,
+    N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, F13, F14, F15,
+    Down, Left, Right, Up,
+    Apostrophe, Backquote, Backslash, Comma, Equal, LeftBracket,
+    Minus, Period, RightBracket, Semicolon, Slash,
+    Backspace, Delete, End, Enter, Escape, Home, Insert, Menu,
+    PageDown, PageUp, Pause, Space, Tab,
+    NumLock, CapsLock, ScrollLock,
+    LeftShift, RightShift, LeftCtrl, RightCtrl, LeftAlt, RightAlt, LeftSuper, RightSuper,
+    NumPad0, NumPad1, NumPad2, NumPad3, NumPad4,
+    NumPad5, NumPad6, NumPad7, NumPad8, NumPad9,
+    NumPadDot, NumPadSlash, NumPadAsterisk, NumPadMinus, NumPadPlus, NumPadEnter
+);
@@ -1,6 +1,9 @@
+use std::{fs::File, io::{BufWriter, Write}, path::Path};
+
+use png::{BitDepth, ColorType, Encoder, EncodingError};
 use simple_linear_algebra::vector::{vec2::Vec2, vec3::Vec3};
 
-use crate::{color::Color, render::image::Image};
+use crate::{color::{BlendMode, Color}, render::image::Image};
 
 #[derive(Clone, Copy, PartialEq, PartialOrd)]
 pub struct BufferSize {
@@ -22,7 +25,7 @@ impl From<BufferSize> for Vec2<Vec2<isize>> {
     fn from(value: BufferSize) -> Self {
         Vec2 {
             x: Vec2::new(0, 0),
-            y: Vec2::new(value.width as isize, value.height as isize)
+            y: Vec2::new(value.width as isize - 1, value.height as isize - 1)
         }
     }
 }
@@ -35,8 +38,9 @@ impl RawBuffer {
         RawBuffer(vec)
     }
 
+    // every pixel is Color::TRANSPARENT, fill it with an opaque colour before presenting
     pub fn init(size: BufferSize) -> Self {
-        let vec = vec![0; size.width * size.height];
+        let vec = vec![Color::TRANSPARENT.0; size.width * size.height];
         Self::new(vec)
     }
 
@@ -48,12 +52,13 @@ impl RawBuffer {
 #[derive(Clone)]
 pub struct Buffer {
     pub raw_buffer: RawBuffer,
-    pub size: BufferSize
+    pub size: BufferSize,
+    pub blend_mode: BlendMode
 }
 
 impl Buffer {
     pub fn new(raw_buffer: RawBuffer, size: BufferSize) -> Self {
-        Self { raw_buffer, size }
+        Self { raw_buffer, size, blend_mode: BlendMode::default() }
     }
 
     pub fn init(size: BufferSize) -> Self {
@@ -72,18 +77,36 @@ impl Buffer {
         self.raw_buffer.0[(y as usize) * self.size.width + x as usize]
     }
 
-    pub fn raw_draw_point(&mut self, point: Vec2<isize>, color: Color) {
+    pub fn set_blend_mode(&mut self, mode: BlendMode) {
+        self.blend_mode = mode;
+    }
+
+    pub fn raw_blend_point(&mut self, point: Vec2<isize>, color: Color, mode: BlendMode) {
         let Vec2 { x, y } = point;
 
-        self.raw_buffer.0[(y as usize) * self.size.width + x as usize] = color.0;
+        let pixel = &mut self.raw_buffer.0[(y as usize) * self.size.width + x as usize];
+
+        *pixel = match mode {
+            BlendMode::Replace => color.0,
+            BlendMode::SourceOver if color.is_opaque() => color.0,
+            mode => color.blend(Color::from_argb_u32(*pixel), mode).0
+        };
     }
 
-    pub fn draw_point(&mut self, point: Vec2<isize>, color: Color) {
+    pub fn blend_point(&mut self, point: Vec2<isize>, color: Color, mode: BlendMode) {
         if point.is_inside_buffer(self.size) {
-            self.raw_draw_point(point, color);
+            self.raw_blend_point(point, color, mode);
         }
     }
 
+    pub fn raw_draw_point(&mut self, point: Vec2<isize>, color: Color) {
+        self.raw_blend_point(point, color, self.blend_mode);
+    }
+
+    pub fn draw_point(&mut self, point: Vec2<isize>, color: Color) {
+        self.blend_point(point, color, self.blend_mode);
+    }
+
     pub fn accuracy_draw_line(&mut self, start: Vec2<isize>, end: Vec2<isize>, color: Color) {
         let (start, end) = if start.is_inside_buffer(self.size) {
             (start, end)
@@ -215,8 +238,8 @@ impl Buffer {
         }
 
         for y in min_y..=max_y {
-            self.draw_point(Vec2::new(y, min_x), color);
-            self.draw_point(Vec2::new(y, max_x), color);
+            self.draw_point(Vec2::new(min_x, y), color);
+            self.draw_point(Vec2::new(max_x, y), color);
         }
     }
 
@@ -243,10 +266,53 @@ impl Buffer {
 
                 let img_point = Vec2::new(x, y);
 
-                self.draw_point(buf_point, Color::new(image.0.get_point(img_point)));
+                self.draw_point(buf_point, Color::from_argb_u32(image.0.get_point(img_point)));
             }
         }
     }
+
+    pub fn to_png<W: Write>(&self, writer: W) -> Result<(), EncodingError> {
+        self.encode_png(writer, ColorType::Rgb)
+    }
+
+    pub(crate) fn encode_png<W: Write>(&self, writer: W, color_type: ColorType) -> Result<(), EncodingError> {
+        let mut encoder = Encoder::new(writer, self.size.width as u32, self.size.height as u32);
+        encoder.set_color(color_type);
+        encoder.set_depth(BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+
+        // the raw buffer may be longer than the visible area after a resize
+        let pixels = &self.raw_buffer.0[..self.size.width * self.size.height];
+
+        let data: Vec<u8> = match color_type {
+            ColorType::Rgba => pixels
+                .iter()
+                .flat_map(|&pixel| {
+                    let (r, g, b) = Color::from_argb_u32(pixel).to_rgb();
+                    [r, g, b, (pixel >> 24) as u8]
+                })
+                .collect(),
+
+            _ => pixels
+                .iter()
+                .flat_map(|&pixel| {
+                    let (r, g, b) = Color::from_argb_u32(pixel).to_rgb();
+                    [r, g, b]
+                })
+                .collect()
+        };
+
+        writer.write_image_data(&data)?;
+
+        writer.finish()
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), EncodingError> {
+        let file = File::create(path)?;
+
+        self.to_png(BufWriter::new(file))
+    }
 }
 
 trait Point {
@@ -286,9 +352,9 @@ pub fn rectangles_intersection(rect_1: Vec2<Vec2<isize>>, rect_2: Vec2<Vec2<isiz
 
     let &y1 = [rect_1.x.y, rect_2.x.y].iter().max().unwrap();
 
-    let &x2 = [rect_1.y.x, rect_2.y.x].iter().max().unwrap();
+    let &x2 = [rect_1.y.x, rect_2.y.x].iter().min().unwrap();
 
-    let &y2 = [rect_1.y.y, rect_2.y.y].iter().max().unwrap();
+    let &y2 = [rect_1.y.y, rect_2.y.y].iter().min().unwrap();
 
     Vec2::new(
         Vec2::new(x1, y1),
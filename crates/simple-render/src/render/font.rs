@@ -0,0 +1,275 @@
+use std::{collections::HashMap, error::Error, fmt::{self, Display}, io, sync::OnceLock};
+
+use simple_linear_algebra::vector::vec2::Vec2;
+
+use crate::{color::Color, render::buffer::{Buffer, BufferSize}};
+
+pub mod bdf;
+pub mod psf;
+pub mod ttf;
+mod builtin;
+
+#[derive(Debug)]
+pub enum FontError {
+    Io(io::Error),
+    // the header does not match the expected format
+    InvalidMagic,
+    // the data ends inside a header, a glyph or a table
+    UnexpectedEnd,
+    // a BDF statement with a missing or broken value, the line is 1-based
+    InvalidLine(usize),
+    // a TrueType table that is required but not in the font
+    MissingTable(&'static str),
+    // a TrueType table with values that make no sense
+    InvalidTable(&'static str)
+}
+
+impl Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontError::Io(err) => write!(f, "{err}"),
+            FontError::InvalidMagic => write!(f, "unknown font format"),
+            FontError::UnexpectedEnd => write!(f, "unexpected end of the font data"),
+            FontError::InvalidLine(line) => write!(f, "line {line}: invalid or missing value"),
+            FontError::MissingTable(tag) => write!(f, "missing '{tag}' table"),
+            FontError::InvalidTable(tag) => write!(f, "invalid '{tag}' table")
+        }
+    }
+}
+
+impl Error for FontError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FontError::Io(err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for FontError {
+    fn from(value: io::Error) -> Self {
+        FontError::Io(value)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Glyph {
+    width: usize,
+    height: usize,
+    // from the pen position at the top of the line to the top left corner of the bitmap
+    offset: Vec2<isize>,
+    advance: usize,
+    // row-major, one entry per pixel
+    bitmap: Vec<bool>
+}
+
+impl Glyph {
+    pub fn new(width: usize, height: usize, offset: Vec2<isize>, advance: usize, bitmap: Vec<bool>) -> Self {
+        assert_eq!(bitmap.len(), width * height, "the bitmap has to be width * height long");
+
+        Self { width, height, offset, advance, bitmap }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn offset(&self) -> Vec2<isize> {
+        self.offset
+    }
+
+    pub fn advance(&self) -> usize {
+        self.advance
+    }
+
+    pub fn is_set(&self, x: usize, y: usize) -> bool {
+        self.bitmap[y * self.width + x]
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BitmapFont {
+    glyphs: HashMap<char, Glyph>,
+    line_height: usize,
+    // from the top of the line to the baseline
+    ascent: usize,
+    // drawn for characters the font has no glyph for
+    fallback: Option<char>
+}
+
+static BUILTIN: OnceLock<BitmapFont> = OnceLock::new();
+
+impl BitmapFont {
+    pub fn new(line_height: usize, ascent: usize) -> Self {
+        Self { glyphs: HashMap::new(), line_height, ascent, fallback: None }
+    }
+
+    // 5x8 monospace printable ascii in a 6x9 cell
+    pub fn builtin() -> &'static BitmapFont {
+        BUILTIN.get_or_init(builtin::font)
+    }
+
+    pub fn insert(&mut self, c: char, glyph: Glyph) {
+        self.glyphs.insert(c, glyph);
+    }
+
+    pub fn set_fallback(&mut self, c: char) {
+        self.fallback = Some(c);
+    }
+
+    pub fn line_height(&self) -> usize {
+        self.line_height
+    }
+
+    pub fn ascent(&self) -> usize {
+        self.ascent
+    }
+
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        self.glyphs.contains_key(&c)
+    }
+
+    // the fallback glyph if there is none for the character
+    pub fn glyph(&self, c: char) -> Option<&Glyph> {
+        self.glyphs.get(&c)
+            .or_else(|| self.glyphs.get(&self.fallback?))
+    }
+
+    // Size of the drawn text, the widest line by the number of lines. Characters without a glyph take no space
+    pub fn measure(&self, text: &str, scale: usize) -> BufferSize {
+        let mut lines = 0;
+        let mut width = 0;
+
+        for line in text.split('\n') {
+            let line_width: usize = line.chars()
+                .filter_map(|c| self.glyph(c))
+                .map(|glyph| glyph.advance)
+                .sum();
+
+            width = width.max(line_width);
+            lines += 1;
+        }
+
+        BufferSize::new(width * scale, lines * self.line_height * scale)
+    }
+}
+
+impl Buffer {
+    // draws with the built-in font, `pos` is the top left corner of the first line
+    pub fn draw_text(&mut self, text: &str, pos: Vec2<isize>, color: Color, scale: usize) {
+        self.draw_text_with_font(BitmapFont::builtin(), text, pos, color, scale);
+    }
+
+    // Every font pixel becomes a scale x scale square, `\n` starts a new line
+    pub fn draw_text_with_font(&mut self, font: &BitmapFont, text: &str, pos: Vec2<isize>, color: Color, scale: usize) {
+        let scale = scale as isize;
+
+        let mut pen = pos;
+
+        for c in text.chars() {
+            if c == '\n' {
+                pen = Vec2::new(pos.x, pen.y + font.line_height as isize * scale);
+                continue;
+            }
+
+            let Some(glyph) = font.glyph(c) else {
+                continue;
+            };
+
+            let origin = pen + glyph.offset * scale;
+
+            for y in 0..glyph.height {
+                for x in 0..glyph.width {
+                    if !glyph.is_set(x, y) {
+                        continue;
+                    }
+
+                    let min = origin + Vec2::new(x as isize, y as isize) * scale;
+
+                    if scale == 1 {
+                        self.draw_point(min, color);
+                    } else {
+                        self.fill_rectangle(Vec2::new(min, min + Vec2::new(scale - 1, scale - 1)), color);
+                    }
+                }
+            }
+
+            pen.x += glyph.advance as isize * scale;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use simple_linear_algebra::vector::vec2::Vec2;
+
+    use crate::{color::Color, render::{buffer::{Buffer, BufferSize}, font::{BitmapFont, Glyph}}};
+
+    fn drawn(text: &str, scale: usize) -> Buffer {
+        let mut buffer = Buffer::init(BufferSize::new(16, 24));
+
+        buffer.fill(Color::BLACK);
+        buffer.draw_text(text, Vec2::new(1, 1), Color::WHITE, scale);
+
+        buffer
+    }
+
+    fn lit(buffer: &Buffer, x: isize, rows: Range<isize>) -> Vec<bool> {
+        rows.map(|y| buffer.get_point(Vec2::new(x, y)) == Color::WHITE.0).collect()
+    }
+
+    #[test]
+    fn measure() {
+        let font = BitmapFont::builtin();
+
+        assert!(font.measure("ab", 1) == BufferSize::new(12, 9));
+        assert!(font.measure("ab\nabc", 2) == BufferSize::new(36, 36));
+        assert!(font.measure("", 1) == BufferSize::new(0, 9));
+
+        // no fallback, so the missing character takes no space
+        let mut font = BitmapFont::new(4, 3);
+        font.insert('a', Glyph::new(2, 2, Vec2::new(0, 1), 3, vec![true; 4]));
+
+        assert!(font.measure("aba", 1) == BufferSize::new(6, 4));
+    }
+
+    #[test]
+    fn draw_text() {
+        // the '!' is a single column, the third one, with a gap in the sixth row
+        let buffer = drawn("!", 1);
+
+        assert_eq!(lit(&buffer, 3, 1..9), [true, true, true, true, true, false, true, false]);
+        assert_eq!(buffer.raw_buffer.0.iter().filter(|&&pixel| pixel == Color::WHITE.0).count(), 6);
+
+        // every pixel becomes a 2x2 square
+        let buffer = drawn("!", 2);
+
+        for x in [5, 6] {
+            assert_eq!(lit(&buffer, x, 1..3), [true; 2]);
+            assert_eq!(lit(&buffer, x, 11..13), [false; 2]);
+        }
+
+        assert_eq!(buffer.raw_buffer.0.iter().filter(|&&pixel| pixel == Color::WHITE.0).count(), 24);
+
+        // the next line starts a line height lower at the same x
+        let buffer = drawn("!\n!", 1);
+        assert_eq!(buffer.get_point(Vec2::new(3, 10)), Color::WHITE.0);
+
+        // characters the font doesn't have are drawn with the fallback
+        assert!(drawn("é", 1).raw_buffer.0 == drawn("?", 1).raw_buffer.0);
+    }
+}
@@ -0,0 +1,219 @@
+use std::{fs::File, io::{BufRead, BufReader}, str::SplitWhitespace};
+
+use simple_linear_algebra::vector::vec2::Vec2;
+
+use crate::render::font::{BitmapFont, FontError, Glyph};
+
+// the glyph being read between STARTCHAR and ENDCHAR
+#[derive(Default)]
+struct PendingGlyph {
+    encoding: Option<i64>,
+    advance: Option<isize>,
+    // width, height, x offset, y offset of the bottom edge above the baseline
+    bbx: Option<[isize; 4]>,
+    rows: Vec<Vec<bool>>,
+    in_bitmap: bool
+}
+
+fn parse_values<const N: usize>(values: SplitWhitespace, line: usize) -> Result<[isize; N], FontError> {
+    let mut result = [0; N];
+    let mut values = values;
+
+    for value in &mut result {
+        *value = values.next()
+            .and_then(|value| value.parse().ok())
+            .ok_or(FontError::InvalidLine(line))?;
+    }
+
+    Ok(result)
+}
+
+// a hex row, padded to whole bytes with the leftmost pixel in the highest bit
+fn parse_row(row: &str, line: usize) -> Result<Vec<bool>, FontError> {
+    let mut pixels = Vec::with_capacity(row.len() * 4);
+
+    for digit in row.chars() {
+        let nibble = digit.to_digit(16).ok_or(FontError::InvalidLine(line))?;
+
+        pixels.extend((0..4).rev().map(|bit| nibble & (1 << bit) != 0));
+    }
+
+    Ok(pixels)
+}
+
+impl BitmapFont {
+    pub fn from_bdf(file: File) -> Result<Self, FontError> {
+        Self::parse_bdf(BufReader::new(file))
+    }
+
+    // Glyphs without an encoding (ENCODING -1) are skipped. The line height is FONT_ASCENT + FONT_DESCENT,
+    // FONTBOUNDINGBOX is used when the properties are missing
+    pub fn parse_bdf<R: BufRead>(reader: R) -> Result<Self, FontError> {
+        let mut bounding_box = None;
+        let mut ascent = None;
+        let mut descent = None;
+        let mut default_advance = None;
+        let mut default_char = None;
+
+        let mut parsed = Vec::new();
+        let mut glyph: Option<PendingGlyph> = None;
+
+        for (index, line) in reader.lines().enumerate() {
+            let number = index + 1;
+            let line = line?;
+
+            let mut values = line.split_whitespace();
+
+            let Some(statement) = values.next() else {
+                continue;
+            };
+
+            if let Some(pending) = glyph.as_mut().filter(|pending| pending.in_bitmap) {
+                if statement == "ENDCHAR" {
+                    parsed.push(glyph.take().unwrap());
+                } else {
+                    pending.rows.push(parse_row(statement, number)?);
+                }
+
+                continue;
+            }
+
+            match (statement, glyph.as_mut()) {
+                ("FONTBOUNDINGBOX", None) => bounding_box = Some(parse_values::<4>(values, number)?),
+                ("FONT_ASCENT", None) => ascent = Some(parse_values::<1>(values, number)?[0]),
+                ("FONT_DESCENT", None) => descent = Some(parse_values::<1>(values, number)?[0]),
+                ("DEFAULT_CHAR", None) => default_char = Some(parse_values::<1>(values, number)?[0]),
+                ("DWIDTH", None) => default_advance = Some(parse_values::<1>(values, number)?[0]),
+
+                ("STARTCHAR", _) => glyph = Some(PendingGlyph::default()),
+                ("ENCODING", Some(pending)) => pending.encoding = Some(parse_values::<1>(values, number)?[0] as i64),
+                ("DWIDTH", Some(pending)) => pending.advance = Some(parse_values::<1>(values, number)?[0]),
+                ("BBX", Some(pending)) => pending.bbx = Some(parse_values::<4>(values, number)?),
+                ("BITMAP", Some(pending)) => pending.in_bitmap = true,
+                ("ENDCHAR", Some(_)) => parsed.push(glyph.take().unwrap()),
+
+                _ => ()
+            }
+        }
+
+        let bounding_box = bounding_box.unwrap_or([0; 4]);
+
+        let ascent = ascent.unwrap_or(bounding_box[1] + bounding_box[3]).max(0);
+        let descent = descent.unwrap_or(-bounding_box[3]).max(0);
+
+        let mut font = BitmapFont::new((ascent + descent) as usize, ascent as usize);
+
+        for pending in parsed {
+            let Some(c) = pending.encoding
+                .and_then(|encoding| u32::try_from(encoding).ok())
+                .and_then(char::from_u32)
+            else {
+                continue;
+            };
+
+            let [width, height, x_offset, y_offset] = pending.bbx.unwrap_or(bounding_box);
+            let (width, height) = (width.max(0) as usize, height.max(0) as usize);
+
+            let mut rows = pending.rows;
+            rows.resize(height, Vec::new());
+
+            // the padding bits are cut off
+            let bitmap = rows
+                .into_iter()
+                .flat_map(|mut row| {
+                    row.resize(width, false);
+                    row
+                })
+                .collect();
+
+            let offset = Vec2::new(x_offset, ascent - (y_offset + height as isize));
+
+            let advance = pending.advance
+                .or(default_advance)
+                .unwrap_or(x_offset + width as isize)
+                .max(0) as usize;
+
+            font.insert(c, Glyph::new(width, height, offset, advance, bitmap));
+        }
+
+        if let Some(c) = default_char.and_then(|c| char::from_u32(c as u32)) {
+            font.set_fallback(c);
+        }
+
+        Ok(font)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use simple_linear_algebra::vector::vec2::Vec2;
+
+    use crate::render::font::{BitmapFont, FontError};
+
+    fn parse(text: &str) -> Result<BitmapFont, FontError> {
+        BitmapFont::parse_bdf(Cursor::new(text))
+    }
+
+    #[test]
+    fn glyph_metrics() {
+        let font = parse("\
+STARTFONT 2.1
+FONTBOUNDINGBOX 6 9 0 -2
+STARTPROPERTIES 2
+FONT_ASCENT 7
+FONT_DESCENT 2
+ENDPROPERTIES
+CHARS 3
+STARTCHAR A
+ENCODING 65
+DWIDTH 5 0
+BBX 3 2 1 1
+BITMAP
+A0
+40
+ENDCHAR
+STARTCHAR unencoded
+ENCODING -1
+BBX 1 1 0 0
+BITMAP
+80
+ENDCHAR
+STARTCHAR B
+ENCODING 66
+BITMAP
+FC
+ENDCHAR
+ENDFONT
+").unwrap();
+
+        assert_eq!((font.line_height(), font.ascent()), (9, 7));
+        assert_eq!(font.len(), 2);
+
+        let a = font.glyph('A').unwrap();
+
+        assert_eq!((a.width(), a.height(), a.advance()), (3, 2, 5));
+        // the bottom edge is one pixel above the baseline
+        assert_eq!(a.offset(), Vec2::new(1, 4));
+        assert_eq!([a.is_set(0, 0), a.is_set(1, 0), a.is_set(2, 0)], [true, false, true]);
+        assert_eq!([a.is_set(0, 1), a.is_set(1, 1), a.is_set(2, 1)], [false, true, false]);
+
+        // the font bounding box, missing rows are empty and the advance is the box width
+        let b = font.glyph('B').unwrap();
+
+        assert_eq!((b.width(), b.height(), b.advance(), b.offset()), (6, 9, 6, Vec2::new(0, 0)));
+        assert!((0..6).all(|x| b.is_set(x, 0)));
+        assert!((0..6).all(|x| !b.is_set(x, 1)));
+    }
+
+    #[test]
+    fn invalid_lines() {
+        assert!(matches!(parse("FONT_ASCENT seven\n"), Err(FontError::InvalidLine(1))));
+        assert!(matches!(parse("STARTCHAR A\nENCODING 65\nBITMAP\nXY\nENDCHAR\n"), Err(FontError::InvalidLine(4))));
+        assert!(matches!(parse("STARTCHAR A\nBBX 1 1\n"), Err(FontError::InvalidLine(2))));
+
+        // a glyph the data ends in is left out
+        assert!(parse("STARTCHAR A\nENCODING 65\nBBX 1 1 0 0\nBITMAP\n80\n").unwrap().is_empty());
+    }
+}
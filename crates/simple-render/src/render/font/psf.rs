@@ -0,0 +1,293 @@
+use std::{fs::File, io::Read};
+
+use simple_linear_algebra::vector::vec2::Vec2;
+
+use crate::render::font::{BitmapFont, FontError, Glyph};
+
+const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+const PSF1_MODE_512: u8 = 0x01;
+const PSF1_MODE_HAS_TABLE: u8 = 0x06;
+const PSF1_SEPARATOR: u16 = 0xFFFF;
+const PSF1_START_SEQUENCE: u16 = 0xFFFE;
+
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xB5, 0x4A, 0x86];
+const PSF2_HAS_TABLE: u32 = 0x01;
+const PSF2_SEPARATOR: u8 = 0xFF;
+const PSF2_START_SEQUENCE: u8 = 0xFE;
+
+fn slice(data: &[u8], start: usize, len: usize) -> Result<&[u8], FontError> {
+    data.get(start..start.checked_add(len).ok_or(FontError::UnexpectedEnd)?)
+        .ok_or(FontError::UnexpectedEnd)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, FontError> {
+    let bytes = slice(data, offset, 4)?;
+
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+// rows padded to whole bytes with the leftmost pixel in the highest bit
+fn glyph(data: &[u8], width: usize, height: usize) -> Glyph {
+    let row_len = width.div_ceil(8);
+
+    let bitmap = data
+        .chunks(row_len.max(1))
+        .take(height)
+        .flat_map(|row| (0..width).map(move |x| row[x / 8] & (0x80 >> (x % 8)) != 0))
+        .collect();
+
+    Glyph::new(width, height, Vec2::new(0, 0), width, bitmap)
+}
+
+// Characters per glyph index, multi-character sequences are skipped since a glyph is drawn per character
+fn psf1_table(data: &[u8], glyphs: usize) -> Result<Vec<Vec<char>>, FontError> {
+    let mut table = vec![Vec::new(); glyphs];
+    let mut offset = 0;
+
+    for chars in &mut table {
+        let mut in_sequence = false;
+
+        loop {
+            let bytes = slice(data, offset, 2)?;
+            let value = u16::from_le_bytes([bytes[0], bytes[1]]);
+            offset += 2;
+
+            match value {
+                PSF1_SEPARATOR => break,
+                PSF1_START_SEQUENCE => in_sequence = true,
+                _ if in_sequence => (),
+                _ => chars.extend(char::from_u32(value as u32))
+            }
+        }
+    }
+
+    Ok(table)
+}
+
+fn psf2_table(data: &[u8], glyphs: usize) -> Result<Vec<Vec<char>>, FontError> {
+    let mut table = vec![Vec::new(); glyphs];
+    let mut offset = 0;
+
+    for chars in &mut table {
+        let end = offset + data[offset..].iter()
+            .position(|&byte| byte == PSF2_SEPARATOR)
+            .ok_or(FontError::UnexpectedEnd)?;
+
+        let entry = &data[offset..end];
+        let singles = entry
+            .iter()
+            .position(|&byte| byte == PSF2_START_SEQUENCE)
+            .map_or(entry, |start| &entry[..start]);
+
+        chars.extend(String::from_utf8_lossy(singles).chars().filter(|&c| c != char::REPLACEMENT_CHARACTER));
+
+        offset = end + 1;
+    }
+
+    Ok(table)
+}
+
+impl BitmapFont {
+    pub fn from_psf(mut file: File) -> Result<Self, FontError> {
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+
+        Self::parse_psf(&data)
+    }
+
+    // PSF1 and PSF2 console fonts. Without a unicode table glyph n is used for the character n
+    pub fn parse_psf(data: &[u8]) -> Result<Self, FontError> {
+        if data.starts_with(&PSF1_MAGIC) {
+            let header = slice(data, 0, 4)?;
+            let (mode, height) = (header[2], header[3] as usize);
+
+            let glyphs = if mode & PSF1_MODE_512 != 0 { 512 } else { 256 };
+            let len = glyphs * height;
+
+            let bitmaps = slice(data, 4, len)?;
+
+            let table = if mode & PSF1_MODE_HAS_TABLE != 0 {
+                Some(psf1_table(&data[4 + len..], glyphs)?)
+            } else {
+                None
+            };
+
+            Ok(Self::from_bitmaps(bitmaps, height, 8, height, table))
+        } else if data.starts_with(&PSF2_MAGIC) {
+            let header_size = read_u32(data, 8)? as usize;
+            let flags = read_u32(data, 12)?;
+            let glyphs = read_u32(data, 16)? as usize;
+            let glyph_size = read_u32(data, 20)? as usize;
+            let height = read_u32(data, 24)? as usize;
+            let width = read_u32(data, 28)? as usize;
+
+            if glyph_size < width.div_ceil(8) * height {
+                return Err(FontError::UnexpectedEnd);
+            }
+
+            let len = glyphs.checked_mul(glyph_size).ok_or(FontError::UnexpectedEnd)?;
+            let bitmaps = slice(data, header_size, len)?;
+
+            let table = if flags & PSF2_HAS_TABLE != 0 {
+                Some(psf2_table(&data[header_size + len..], glyphs)?)
+            } else {
+                None
+            };
+
+            Ok(Self::from_bitmaps(bitmaps, glyph_size, width, height, table))
+        } else {
+            Err(FontError::InvalidMagic)
+        }
+    }
+
+    fn from_bitmaps(
+        bitmaps: &[u8],
+        glyph_size: usize,
+        width: usize,
+        height: usize,
+        table: Option<Vec<Vec<char>>>
+    ) -> Self {
+        let mut font = BitmapFont::new(height, height);
+
+        if glyph_size == 0 {
+            return font;
+        }
+
+        for (index, bitmap) in bitmaps.chunks(glyph_size).enumerate() {
+            font.insert_indexed(index, glyph(bitmap, width, height), table.as_ref());
+        }
+
+        font
+    }
+
+    fn insert_indexed(&mut self, index: usize, glyph: Glyph, table: Option<&Vec<Vec<char>>>) {
+        match table {
+            Some(table) => {
+                for &c in &table[index] {
+                    self.insert(c, glyph.clone());
+                }
+            },
+            None => {
+                if let Some(c) = char::from_u32(index as u32) {
+                    self.insert(c, glyph);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render::font::{BitmapFont, FontError};
+
+    // 256 glyphs two rows high, glyph 'A' has the top left and bottom right pixel set
+    fn psf1(mode: u8) -> Vec<u8> {
+        let mut data = vec![0x36, 0x04, mode, 2];
+
+        data.extend((0..256).flat_map(|index| if index == 'A' as usize { [0x80, 0x01] } else { [0, 0] }));
+
+        data
+    }
+
+    // two glyphs ten pixels wide and three high, the second one has a full first row
+    fn psf2(flags: u32) -> Vec<u8> {
+        let mut data = vec![0x72, 0xB5, 0x4A, 0x86];
+
+        for value in [0, 32, flags, 2, 6, 3, 10] {
+            data.extend(u32::to_le_bytes(value));
+        }
+
+        data.extend([0; 6]);
+        data.extend([0xFF, 0xC0, 0x00, 0x00, 0x00, 0x40]);
+
+        data
+    }
+
+    #[test]
+    fn psf1_glyphs() {
+        let font = BitmapFont::parse_psf(&psf1(0)).unwrap();
+
+        assert_eq!((font.len(), font.line_height()), (256, 2));
+
+        let a = font.glyph('A').unwrap();
+
+        assert_eq!((a.width(), a.height(), a.advance()), (8, 2, 8));
+        assert!(a.is_set(0, 0) && a.is_set(7, 1));
+        assert!(!a.is_set(7, 0) && !a.is_set(0, 1));
+    }
+
+    #[test]
+    fn psf1_unicode_table() {
+        let mut data = psf1(0x02);
+
+        for index in 0..256 {
+            let entry: &[u16] = match index {
+                // 'é' on its own, then a sequence that is skipped
+                0x41 => &[0xE9, 0xFFFE, 0x61, 0x0301],
+                _ => &[]
+            };
+
+            data.extend(entry.iter().chain(&[0xFFFF]).flat_map(|value| value.to_le_bytes()));
+        }
+
+        let font = BitmapFont::parse_psf(&data).unwrap();
+
+        assert_eq!(font.len(), 1);
+        assert!(font.glyph('é').unwrap().is_set(0, 0));
+        assert!(!font.contains('a') && !font.contains('A'));
+    }
+
+    #[test]
+    fn psf2_glyphs() {
+        let font = BitmapFont::parse_psf(&psf2(0)).unwrap();
+
+        assert_eq!((font.len(), font.line_height()), (2, 3));
+
+        let glyph = font.glyph('\u{1}').unwrap();
+
+        assert_eq!((glyph.width(), glyph.height(), glyph.advance()), (10, 3, 10));
+        assert!((0..10).all(|x| glyph.is_set(x, 0)));
+        assert!((0..9).all(|x| !glyph.is_set(x, 2)));
+        assert!(glyph.is_set(9, 2));
+    }
+
+    #[test]
+    fn psf2_unicode_table() {
+        let mut data = psf2(0x01);
+
+        data.extend(b"x\xFF");
+        data.extend("€".as_bytes());
+        data.extend(b"\xFEab\xFF");
+
+        let font = BitmapFont::parse_psf(&data).unwrap();
+
+        assert_eq!(font.len(), 2);
+        assert!(font.glyph('€').unwrap().is_set(0, 0));
+        assert!(!font.glyph('x').unwrap().is_set(0, 0));
+        assert!(!font.contains('a'));
+    }
+
+    #[test]
+    fn invalid_data() {
+        assert!(matches!(BitmapFont::parse_psf(&[]), Err(FontError::InvalidMagic)));
+        assert!(matches!(BitmapFont::parse_psf(b"STARTFONT 2.1"), Err(FontError::InvalidMagic)));
+
+        let psf1 = psf1(0);
+        assert!(matches!(BitmapFont::parse_psf(&psf1[..100]), Err(FontError::UnexpectedEnd)));
+
+        // the table has no entries
+        let mut with_table = psf1.clone();
+        with_table[2] = 0x02;
+        assert!(matches!(BitmapFont::parse_psf(&with_table), Err(FontError::UnexpectedEnd)));
+
+        let psf2 = psf2(0);
+        assert!(matches!(BitmapFont::parse_psf(&psf2[..20]), Err(FontError::UnexpectedEnd)));
+        assert!(matches!(BitmapFont::parse_psf(&psf2[..40]), Err(FontError::UnexpectedEnd)));
+
+        // the last table entry is missing its separator
+        let mut with_table = psf2.clone();
+        with_table[12] = 0x01;
+        with_table.extend(b"x\xFFy");
+        assert!(matches!(BitmapFont::parse_psf(&with_table), Err(FontError::UnexpectedEnd)));
+    }
+}
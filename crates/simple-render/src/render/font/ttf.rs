@@ -0,0 +1,809 @@
+use std::{collections::HashMap, fs::File, io::Read, sync::{Arc, Mutex}};
+
+use simple_linear_algebra::vector::vec2::Vec2;
+
+use crate::{color::Color, render::{buffer::{Buffer, BufferSize}, font::FontError, raster::Rasterizer}};
+
+const TRUETYPE_VERSION: u32 = 0x0001_0000;
+const APPLE_TRUETYPE_VERSION: &[u8; 4] = b"true";
+
+// how far the flattened curves may be off, in pixels
+const TOLERANCE: f64 = 0.2;
+// deeper nesting of composite glyphs is treated as broken
+const MAX_COMPONENT_DEPTH: usize = 8;
+// code points past it can never be a char
+const MAX_CODE_POINT: u32 = 0x10_FFFF;
+
+// simple glyph flags
+const ON_CURVE: u8 = 0x01;
+const X_SHORT: u8 = 0x02;
+const Y_SHORT: u8 = 0x04;
+const REPEAT: u8 = 0x08;
+const X_SAME_OR_POSITIVE: u8 = 0x10;
+const Y_SAME_OR_POSITIVE: u8 = 0x20;
+
+// composite glyph flags
+const ARGS_ARE_WORDS: u16 = 0x0001;
+const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+const HAS_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const HAS_XY_SCALE: u16 = 0x0040;
+const HAS_2X2: u16 = 0x0080;
+
+fn slice(data: &[u8], start: usize, len: usize) -> Result<&[u8], FontError> {
+    data.get(start..start.checked_add(len).ok_or(FontError::UnexpectedEnd)?)
+        .ok_or(FontError::UnexpectedEnd)
+}
+
+fn read_u8(data: &[u8], offset: usize) -> Result<u8, FontError> {
+    data.get(offset).copied().ok_or(FontError::UnexpectedEnd)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, FontError> {
+    let bytes = slice(data, offset, 2)?;
+
+    Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_i16(data: &[u8], offset: usize) -> Result<i16, FontError> {
+    read_u16(data, offset).map(|value| value as i16)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, FontError> {
+    let bytes = slice(data, offset, 4)?;
+
+    Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+// 2.14 fixed point
+fn read_f2dot14(data: &[u8], offset: usize) -> Result<f64, FontError> {
+    read_i16(data, offset).map(|value| value as f64 / 16384.0)
+}
+
+#[derive(Clone, Copy, Debug)]
+struct OutlinePoint {
+    pos: Vec2<f64>,
+    on_curve: bool
+}
+
+// A glyph rasterised at one size. The coverage is row-major, 0 is transparent and 255 opaque
+#[derive(Clone, Debug)]
+pub struct RasterGlyph {
+    width: usize,
+    height: usize,
+    // from the pen position on the baseline to the top left corner of the bitmap
+    offset: Vec2<isize>,
+    coverage: Vec<u8>
+}
+
+impl RasterGlyph {
+    fn empty() -> Self {
+        Self { width: 0, height: 0, offset: Vec2::new(0, 0), coverage: Vec::new() }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn offset(&self) -> Vec2<isize> {
+        self.offset
+    }
+
+    pub fn coverage(&self, x: usize, y: usize) -> u8 {
+        self.coverage[y * self.width + x]
+    }
+}
+
+// Scalable TrueType font with quadratic outlines. Sizes are in pixels per em,
+// the rasterised glyphs are kept per size until `clear_cache`
+pub struct TrueTypeFont {
+    data: Vec<u8>,
+    units_per_em: f64,
+    ascender: i16,
+    descender: i16,
+    line_gap: i16,
+    // where every glyph starts in the glyf table, one more entry for the end of the last one
+    locations: Vec<usize>,
+    glyf: usize,
+    // advance width of every glyph
+    advances: Vec<u16>,
+    cmap: HashMap<char, u16>,
+    kerning: HashMap<(u16, u16), i16>,
+    cache: Mutex<HashMap<(u16, u64), Arc<RasterGlyph>>>
+}
+
+impl TrueTypeFont {
+    pub fn from_ttf(mut file: File) -> Result<Self, FontError> {
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+
+        Self::parse_ttf(data)
+    }
+
+    pub fn parse_ttf(data: Vec<u8>) -> Result<Self, FontError> {
+        let version = read_u32(&data, 0)?;
+
+        if version != TRUETYPE_VERSION && &version.to_be_bytes() != APPLE_TRUETYPE_VERSION {
+            return Err(FontError::InvalidMagic);
+        }
+
+        let mut tables = HashMap::new();
+
+        for index in 0..read_u16(&data, 4)? as usize {
+            let record = 12 + index * 16;
+
+            let tag = slice(&data, record, 4)?;
+            let offset = read_u32(&data, record + 8)? as usize;
+            let len = read_u32(&data, record + 12)? as usize;
+
+            slice(&data, offset, len)?;
+
+            tables.insert([tag[0], tag[1], tag[2], tag[3]], offset);
+        }
+
+        let table = |tag: &'static str| {
+            tables.get(tag.as_bytes()).copied().ok_or(FontError::MissingTable(tag))
+        };
+
+        let head = table("head")?;
+        let units_per_em = read_u16(&data, head + 18)?;
+        let long_locations = read_i16(&data, head + 50)? != 0;
+
+        if units_per_em == 0 {
+            return Err(FontError::InvalidTable("head"));
+        }
+
+        let glyph_count = read_u16(&data, table("maxp")? + 4)? as usize;
+
+        let hhea = table("hhea")?;
+        let ascender = read_i16(&data, hhea + 4)?;
+        let descender = read_i16(&data, hhea + 6)?;
+        let line_gap = read_i16(&data, hhea + 8)?;
+        let metrics_count = read_u16(&data, hhea + 34)? as usize;
+
+        if metrics_count == 0 {
+            return Err(FontError::InvalidTable("hhea"));
+        }
+
+        // the last advance repeats for the glyphs after the long metrics
+        let hmtx = table("hmtx")?;
+        let advances = (0..glyph_count)
+            .map(|glyph| read_u16(&data, hmtx + glyph.min(metrics_count - 1) * 4))
+            .collect::<Result<_, _>>()?;
+
+        let loca = table("loca")?;
+        let locations = (0..=glyph_count)
+            .map(|glyph| match long_locations {
+                true => read_u32(&data, loca + glyph * 4).map(|offset| offset as usize),
+                false => read_u16(&data, loca + glyph * 2).map(|offset| offset as usize * 2)
+            })
+            .collect::<Result<_, _>>()?;
+
+        let cmap = parse_cmap(&data, table("cmap")?, glyph_count)?;
+
+        let kerning = match table("kern") {
+            Ok(kern) => parse_kern(&data, kern)?,
+            Err(_) => HashMap::new()
+        };
+
+        Ok(Self {
+            units_per_em: units_per_em as f64,
+            ascender,
+            descender,
+            line_gap,
+            locations,
+            glyf: table("glyf")?,
+            advances,
+            cmap,
+            kerning,
+            cache: Mutex::new(HashMap::new()),
+            data
+        })
+    }
+
+    pub fn glyph_count(&self) -> usize {
+        self.advances.len()
+    }
+
+    // 0 is the missing glyph every font has
+    pub fn glyph_index(&self, c: char) -> u16 {
+        self.cmap.get(&c).copied().unwrap_or(0)
+    }
+
+    fn scale(&self, size: f64) -> f64 {
+        size / self.units_per_em
+    }
+
+    // from the top of the line to the baseline, in pixels
+    pub fn ascent(&self, size: f64) -> f64 {
+        self.ascender as f64 * self.scale(size)
+    }
+
+    // below the baseline, negative
+    pub fn descent(&self, size: f64) -> f64 {
+        self.descender as f64 * self.scale(size)
+    }
+
+    pub fn line_height(&self, size: f64) -> f64 {
+        (self.ascender as f64 - self.descender as f64 + self.line_gap as f64) * self.scale(size)
+    }
+
+    pub fn advance(&self, glyph: u16, size: f64) -> f64 {
+        self.advances.get(glyph as usize).copied().unwrap_or(0) as f64 * self.scale(size)
+    }
+
+    // added to the advance of `left` when `right` follows it
+    pub fn kerning(&self, left: u16, right: u16, size: f64) -> f64 {
+        self.kerning.get(&(left, right)).copied().unwrap_or(0) as f64 * self.scale(size)
+    }
+
+    // Size of the drawn text in pixels, rounded up. Lines are split on '\n'
+    pub fn measure(&self, text: &str, size: f64) -> BufferSize {
+        let mut lines = 0;
+        let mut width: f64 = 0.0;
+
+        for line in text.split('\n') {
+            let mut line_width = 0.0;
+            let mut previous = None;
+
+            for c in line.chars() {
+                let glyph = self.glyph_index(c);
+
+                if let Some(previous) = previous {
+                    line_width += self.kerning(previous, glyph, size);
+                }
+
+                line_width += self.advance(glyph, size);
+                previous = Some(glyph);
+            }
+
+            width = width.max(line_width);
+            lines += 1;
+        }
+
+        BufferSize::new(width.ceil() as usize, (lines as f64 * self.line_height(size)).ceil() as usize)
+    }
+
+    // rasterises the glyph on the first request for a size, later ones share the cached bitmap
+    pub fn raster_glyph(&self, glyph: u16, size: f64) -> Arc<RasterGlyph> {
+        let key = (glyph, size.to_bits());
+
+        if let Some(raster) = self.cache.lock().unwrap().get(&key) {
+            return raster.clone();
+        }
+
+        // a broken outline is drawn as an empty glyph
+        let raster = Arc::new(self.rasterise(glyph, size).unwrap_or_else(|_| RasterGlyph::empty()));
+
+        self.cache.lock().unwrap().insert(key, raster.clone());
+
+        raster
+    }
+
+    pub fn cached_glyphs(&self) -> usize {
+        self.cache.lock().unwrap().len()
+    }
+
+    pub fn clear_cache(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+
+    fn rasterise(&self, glyph: u16, size: f64) -> Result<RasterGlyph, FontError> {
+        let contours = self.outline(glyph, 0)?;
+        let scale = self.scale(size);
+
+        let points = contours.iter().flatten().map(|point| point.pos);
+
+        let (mut min, mut max) = (Vec2::splat(f64::INFINITY), Vec2::splat(f64::NEG_INFINITY));
+
+        for pos in points {
+            min = Vec2::new(min.x.min(pos.x), min.y.min(pos.y));
+            max = Vec2::new(max.x.max(pos.x), max.y.max(pos.y));
+        }
+
+        if contours.is_empty() || min.x > max.x {
+            return Ok(RasterGlyph::empty());
+        }
+
+        // the font's y axis points up, the buffer's down
+        let left = (min.x * scale).floor();
+        let top = (-max.y * scale).floor();
+
+        let width = ((max.x * scale).ceil() - left) as usize + 1;
+        let height = ((-min.y * scale).ceil() - top) as usize + 1;
+
+        let to_pixels = |pos: Vec2<f64>| Vec2::new(pos.x * scale - left, -pos.y * scale - top);
+
+        let mut rasterizer = Rasterizer::new(width, height);
+
+        for contour in &contours {
+            // the contour starts at an on-curve point, or between two off-curve ones
+            let Some(start) = contour.iter().position(|point| point.on_curve) else {
+                let first = to_pixels(midpoint(contour[contour.len() - 1].pos, contour[0].pos));
+                trace(&mut rasterizer, first, contour.iter().map(|point| OutlinePoint {
+                    pos: to_pixels(point.pos),
+                    on_curve: false
+                }));
+
+                continue;
+            };
+
+            let points = contour[start + 1..].iter()
+                .chain(&contour[..=start])
+                .map(|point| OutlinePoint { pos: to_pixels(point.pos), on_curve: point.on_curve });
+
+            trace(&mut rasterizer, to_pixels(contour[start].pos), points);
+        }
+
+        Ok(RasterGlyph {
+            width,
+            height,
+            offset: Vec2::new(left as isize, top as isize),
+            coverage: rasterizer.coverage()
+        })
+    }
+
+    // contours in font units, components of composite glyphs already transformed
+    fn outline(&self, glyph: u16, depth: usize) -> Result<Vec<Vec<OutlinePoint>>, FontError> {
+        if depth > MAX_COMPONENT_DEPTH {
+            return Err(FontError::InvalidTable("glyf"));
+        }
+
+        let index = glyph as usize;
+
+        let (Some(&start), Some(&end)) = (self.locations.get(index), self.locations.get(index + 1)) else {
+            return Ok(Vec::new());
+        };
+
+        // glyphs without an outline, like the space, take no bytes
+        if end <= start {
+            return Ok(Vec::new());
+        }
+
+        let data = slice(&self.data, self.glyf + start, end - start)?;
+        let contour_count = read_i16(data, 0)?;
+
+        if contour_count >= 0 {
+            simple_outline(data, contour_count as usize)
+        } else {
+            self.composite_outline(data, depth)
+        }
+    }
+
+    fn composite_outline(&self, data: &[u8], depth: usize) -> Result<Vec<Vec<OutlinePoint>>, FontError> {
+        let mut contours = Vec::new();
+        let mut offset = 10;
+
+        loop {
+            let flags = read_u16(data, offset)?;
+            let component = read_u16(data, offset + 2)?;
+            offset += 4;
+
+            let (dx, dy) = if flags & ARGS_ARE_WORDS != 0 {
+                offset += 4;
+                (read_i16(data, offset - 4)? as f64, read_i16(data, offset - 2)? as f64)
+            } else {
+                offset += 2;
+                (read_u8(data, offset - 2)? as i8 as f64, read_u8(data, offset - 1)? as i8 as f64)
+            };
+
+            // anchor point matching is not supported, such components stay in place
+            let translation = if flags & ARGS_ARE_XY_VALUES != 0 { Vec2::new(dx, dy) } else { Vec2::new(0.0, 0.0) };
+
+            // columns of the 2x2 transform
+            let (x_axis, y_axis) = if flags & HAS_SCALE != 0 {
+                offset += 2;
+                let scale = read_f2dot14(data, offset - 2)?;
+                (Vec2::new(scale, 0.0), Vec2::new(0.0, scale))
+            } else if flags & HAS_XY_SCALE != 0 {
+                offset += 4;
+                (Vec2::new(read_f2dot14(data, offset - 4)?, 0.0), Vec2::new(0.0, read_f2dot14(data, offset - 2)?))
+            } else if flags & HAS_2X2 != 0 {
+                offset += 8;
+                (
+                    Vec2::new(read_f2dot14(data, offset - 8)?, read_f2dot14(data, offset - 6)?),
+                    Vec2::new(read_f2dot14(data, offset - 4)?, read_f2dot14(data, offset - 2)?)
+                )
+            } else {
+                (Vec2::new(1.0, 0.0), Vec2::new(0.0, 1.0))
+            };
+
+            for mut contour in self.outline(component, depth + 1)? {
+                for point in &mut contour {
+                    point.pos = x_axis * point.pos.x + y_axis * point.pos.y + translation;
+                }
+
+                contours.push(contour);
+            }
+
+            if flags & MORE_COMPONENTS == 0 {
+                return Ok(contours);
+            }
+        }
+    }
+}
+
+fn midpoint(a: Vec2<f64>, b: Vec2<f64>) -> Vec2<f64> {
+    (a + b) * 0.5
+}
+
+// Follows a closed contour from `start`, two off-curve points in a row have an implied on-curve point between them
+fn trace(rasterizer: &mut Rasterizer, start: Vec2<f64>, points: impl Iterator<Item = OutlinePoint>) {
+    let mut current = start;
+    let mut control: Option<Vec2<f64>> = None;
+
+    for point in points {
+        match (control, point.on_curve) {
+            (None, true) => {
+                rasterizer.line(current, point.pos);
+                current = point.pos;
+            },
+            (None, false) => control = Some(point.pos),
+            (Some(previous), true) => {
+                rasterizer.quad(current, previous, point.pos, TOLERANCE);
+                current = point.pos;
+                control = None;
+            },
+            (Some(previous), false) => {
+                let middle = midpoint(previous, point.pos);
+
+                rasterizer.quad(current, previous, middle, TOLERANCE);
+                current = middle;
+                control = Some(point.pos);
+            }
+        }
+    }
+
+    match control {
+        Some(previous) => rasterizer.quad(current, previous, start, TOLERANCE),
+        None => rasterizer.line(current, start)
+    }
+}
+
+fn simple_outline(data: &[u8], contour_count: usize) -> Result<Vec<Vec<OutlinePoint>>, FontError> {
+    let ends = (0..contour_count)
+        .map(|contour| read_u16(data, 10 + contour * 2).map(|end| end as usize))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let Some(&last) = ends.last() else {
+        return Ok(Vec::new());
+    };
+
+    let point_count = last + 1;
+
+    let instructions = read_u16(data, 10 + contour_count * 2)? as usize;
+    let mut offset = 12 + contour_count * 2 + instructions;
+
+    let mut flags = Vec::with_capacity(point_count);
+
+    while flags.len() < point_count {
+        let flag = read_u8(data, offset)?;
+        offset += 1;
+
+        let repeat = if flag & REPEAT != 0 {
+            offset += 1;
+            read_u8(data, offset - 1)? as usize
+        } else {
+            0
+        };
+
+        flags.extend(std::iter::repeat_n(flag, repeat + 1));
+    }
+
+    flags.truncate(point_count);
+
+    let mut read_coordinates = |short: u8, same_or_positive: u8| -> Result<Vec<f64>, FontError> {
+        let mut value = 0i32;
+
+        flags.iter().map(|&flag| {
+            if flag & short != 0 {
+                let delta = read_u8(data, offset)? as i32;
+                offset += 1;
+                value += if flag & same_or_positive != 0 { delta } else { -delta };
+            } else if flag & same_or_positive == 0 {
+                value += read_i16(data, offset)? as i32;
+                offset += 2;
+            }
+
+            Ok(value as f64)
+        }).collect()
+    };
+
+    let xs = read_coordinates(X_SHORT, X_SAME_OR_POSITIVE)?;
+    let ys = read_coordinates(Y_SHORT, Y_SAME_OR_POSITIVE)?;
+
+    let mut contours = Vec::with_capacity(contour_count);
+    let mut first = 0;
+
+    for end in ends {
+        if end < first || end >= point_count {
+            return Err(FontError::InvalidTable("glyf"));
+        }
+
+        contours.push((first..=end).map(|point| OutlinePoint {
+            pos: Vec2::new(xs[point], ys[point]),
+            on_curve: flags[point] & ON_CURVE != 0
+        }).collect());
+
+        first = end + 1;
+    }
+
+    Ok(contours)
+}
+
+// Unicode subtables only, format 12 is preferred since format 4 is limited to the BMP.
+// A glyph id outside of the font makes the table invalid
+fn parse_cmap(data: &[u8], cmap: usize, glyph_count: usize) -> Result<HashMap<char, u16>, FontError> {
+    let mut best = None;
+
+    for index in 0..read_u16(data, cmap + 2)? as usize {
+        let record = cmap + 4 + index * 8;
+
+        let platform = read_u16(data, record)?;
+        let encoding = read_u16(data, record + 2)?;
+        let subtable = cmap + read_u32(data, record + 4)? as usize;
+
+        let unicode = platform == 0 || (platform == 3 && (encoding == 1 || encoding == 10));
+
+        if !unicode {
+            continue;
+        }
+
+        match read_u16(data, subtable)? {
+            12 => best = Some((12, subtable)),
+            4 if best.is_none() => best = Some((4, subtable)),
+            _ => ()
+        }
+    }
+
+    let mut map = HashMap::new();
+
+    match best {
+        Some((12, subtable)) => {
+            for group in 0..read_u32(data, subtable + 12)? as usize {
+                let record = subtable + 16 + group * 12;
+
+                let first = read_u32(data, record)?;
+                let last = read_u32(data, record + 4)?.min(MAX_CODE_POINT);
+                let glyph = read_u32(data, record + 8)?;
+
+                if first > last {
+                    continue;
+                }
+
+                let last_glyph = glyph.checked_add(last - first)
+                    .filter(|&last_glyph| (last_glyph as usize) < glyph_count)
+                    .ok_or(FontError::InvalidTable("cmap"))?;
+
+                for (code, glyph) in (first..=last).zip(glyph..=last_glyph) {
+                    if let Some(c) = char::from_u32(code) {
+                        map.insert(c, glyph as u16);
+                    }
+                }
+            }
+        },
+        Some((_, subtable)) => {
+            let segments = read_u16(data, subtable + 6)? as usize / 2;
+
+            let ends = subtable + 14;
+            let starts = ends + segments * 2 + 2;
+            let deltas = starts + segments * 2;
+            let range_offsets = deltas + segments * 2;
+
+            for segment in 0..segments {
+                let end = read_u16(data, ends + segment * 2)?;
+                let start = read_u16(data, starts + segment * 2)?;
+                let delta = read_u16(data, deltas + segment * 2)?;
+                let range_offset = read_u16(data, range_offsets + segment * 2)? as usize;
+
+                for code in start..=end {
+                    // the last segment maps 0xFFFF to the missing glyph
+                    if code == 0xFFFF {
+                        break;
+                    }
+
+                    let glyph = if range_offset == 0 {
+                        code.wrapping_add(delta)
+                    } else {
+                        // relative to the range offset entry itself
+                        let address = range_offsets + segment * 2 + range_offset + (code - start) as usize * 2;
+
+                        match read_u16(data, address)? {
+                            0 => 0,
+                            glyph => glyph.wrapping_add(delta)
+                        }
+                    };
+
+                    if glyph as usize >= glyph_count {
+                        return Err(FontError::InvalidTable("cmap"));
+                    }
+
+                    if let Some(c) = char::from_u32(code as u32).filter(|_| glyph != 0) {
+                        map.insert(c, glyph);
+                    }
+                }
+            }
+        },
+        None => return Err(FontError::InvalidTable("cmap"))
+    }
+
+    Ok(map)
+}
+
+// Horizontal format 0 subtables of the Windows kern table, other formats are ignored
+fn parse_kern(data: &[u8], kern: usize) -> Result<HashMap<(u16, u16), i16>, FontError> {
+    let mut pairs = HashMap::new();
+
+    if read_u16(data, kern)? != 0 {
+        return Ok(pairs);
+    }
+
+    let mut subtable = kern + 4;
+
+    for _ in 0..read_u16(data, kern + 2)? {
+        let mut len = read_u16(data, subtable + 2)? as usize;
+        let coverage = read_u16(data, subtable + 4)?;
+
+        if coverage >> 8 == 0 {
+            let pair_count = read_u16(data, subtable + 6)? as usize;
+
+            // the 16 bit length overflows in fonts with many pairs
+            len = 14 + pair_count * 6;
+
+            // horizontal, not minimum values, not cross-stream
+            if coverage & 0x07 == 0x01 {
+                for pair in 0..pair_count {
+                    let record = subtable + 14 + pair * 6;
+
+                    pairs.insert(
+                        (read_u16(data, record)?, read_u16(data, record + 2)?),
+                        read_i16(data, record + 4)?
+                    );
+                }
+            }
+        }
+
+        subtable += len;
+    }
+
+    Ok(pairs)
+}
+
+impl Buffer {
+    // `pos` is the top left corner of the first line, `size` is in pixels per em. Glyphs are placed on whole pixels
+    pub fn draw_truetype_text(&mut self, font: &TrueTypeFont, text: &str, pos: Vec2<isize>, color: Color, size: f64) {
+        let ascent = font.ascent(size).round() as isize;
+        let line_height = font.line_height(size);
+
+        for (index, line) in text.split('\n').enumerate() {
+            let baseline = pos.y + (index as f64 * line_height).round() as isize + ascent;
+
+            let mut pen = pos.x as f64;
+            let mut previous = None;
+
+            for c in line.chars() {
+                let glyph = font.glyph_index(c);
+
+                if let Some(previous) = previous {
+                    pen += font.kerning(previous, glyph, size);
+                }
+
+                let raster = font.raster_glyph(glyph, size);
+                let origin = Vec2::new(pen.round() as isize, baseline) + raster.offset;
+
+                for y in 0..raster.height {
+                    for x in 0..raster.width {
+                        let coverage = raster.coverage(x, y);
+
+                        if coverage != 0 {
+                            self.draw_point(origin + Vec2::new(x as isize, y as isize), color.fade(coverage));
+                        }
+                    }
+                }
+
+                pen += font.advance(glyph, size);
+                previous = Some(glyph);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simple_linear_algebra::vector::vec2::Vec2;
+
+    use crate::render::font::{FontError, ttf::{parse_cmap, simple_outline}};
+
+    fn bytes(words: &[(u32, usize)]) -> Vec<u8> {
+        words.iter()
+            .flat_map(|&(value, len)| value.to_be_bytes()[4 - len..].to_vec())
+            .collect()
+    }
+
+    // a cmap table with one Windows Unicode subtable
+    fn cmap(encoding: u32, subtable: &[(u32, usize)]) -> Vec<u8> {
+        let mut data = bytes(&[(0, 2), (1, 2), (3, 2), (encoding, 2), (12, 4)]);
+        data.extend(bytes(subtable));
+        data
+    }
+
+    #[test]
+    fn simple_outline_decoding() {
+        let data = bytes(&[
+            // two contours, the bounding box is not read
+            (2, 2), (0, 4), (0, 4),
+            (1, 2), (3, 2),
+            // one instruction byte to skip
+            (1, 2), (0xAA, 1),
+            // on curve with short +x and the same y, off curve with short -x and a word y,
+            // then a repeated on curve flag with a word x and a short +y
+            (0x33, 1), (0x02, 1), (0x2D, 1), (1, 1),
+            (10, 1), (4, 1), (0xFF9C, 2), (0xFF9C, 2),
+            (300, 2), (5, 1), (5, 1)
+        ]);
+
+        let contours = simple_outline(&data, 2).unwrap();
+
+        let decoded = contours.iter()
+            .map(|contour| contour.iter().map(|point| (point.pos, point.on_curve)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        assert_eq!(decoded, vec![
+            vec![(Vec2::new(10.0, 0.0), true), (Vec2::new(6.0, 300.0), false)],
+            vec![(Vec2::new(-94.0, 305.0), true), (Vec2::new(-194.0, 310.0), true)]
+        ]);
+
+        assert!(matches!(simple_outline(&data[..data.len() - 1], 2), Err(FontError::UnexpectedEnd)));
+    }
+
+    #[test]
+    fn cmap_format_4() {
+        let data = cmap(1, &[
+            (4, 2), (0, 2), (0, 2), (6, 2), (0, 2), (0, 2), (0, 2),
+            // a-c by delta, 0-1 through the glyph array and the closing segment
+            (0x63, 2), (0x31, 2), (0xFFFF, 2), (0, 2),
+            (0x61, 2), (0x30, 2), (0xFFFF, 2),
+            (0xFFA0, 2), (0, 2), (1, 2),
+            (0, 2), (4, 2), (0, 2),
+            (7, 2), (0, 2)
+        ]);
+
+        let map = parse_cmap(&data, 0, 8).unwrap();
+
+        assert_eq!(map.len(), 4);
+        assert_eq!(map[&'a'], 1);
+        assert_eq!(map[&'c'], 3);
+        assert_eq!(map[&'0'], 7);
+        assert!(!map.contains_key(&'1'));
+
+        assert!(matches!(parse_cmap(&data, 0, 7), Err(FontError::InvalidTable("cmap"))));
+    }
+
+    #[test]
+    fn cmap_format_12() {
+        let table = |groups: &[(u32, u32, u32)]| {
+            let mut subtable = vec![(12, 2), (0, 2), (0, 4), (0, 4), (groups.len() as u32, 4)];
+            subtable.extend(groups.iter().flat_map(|&(first, last, glyph)| [(first, 4), (last, 4), (glyph, 4)]));
+
+            cmap(10, &subtable)
+        };
+
+        // the last group runs past the Unicode range
+        let data = table(&[(0x41, 0x43, 1), (0x1F600, 0x1F600, 4), (0x10FFFF, u32::MAX, 5), (u32::MAX, u32::MAX, 1)]);
+        let map = parse_cmap(&data, 0, 6).unwrap();
+
+        assert_eq!(map.len(), 5);
+        assert_eq!(map[&'A'], 1);
+        assert_eq!(map[&'C'], 3);
+        assert_eq!(map[&'\u{1F600}'], 4);
+        assert_eq!(map[&'\u{10FFFF}'], 5);
+
+        for groups in [[(0x41, 0x43, 4)], [(0x41, 0x42, u32::MAX)], [(0x41, 0x41, 0x1_0000)]] {
+            assert!(matches!(parse_cmap(&table(&groups), 0, 6), Err(FontError::InvalidTable("cmap"))), "{groups:?}");
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::sync::{Arc, Mutex};
+
+use crate::render::{app_handler::{AppHandler, Event}, buffer::{Buffer, BufferSize}, input::Key};
+
+// Drives the same event/redraw cycle as `Render::run`, but into an in-memory buffer
+pub struct Headless<T> {
+    app: Arc<Mutex<T>>,
+    buffer: Buffer,
+    frames: usize,
+    frame: usize,
+    // one `AppHandler::update` step of this length runs every frame
+    dt: f64,
+    // scripted input, sent at the start of the frame
+    events: Vec<(usize, Event<'static>)>
+}
+
+impl<T: AppHandler> Headless<T> {
+    pub fn new(app: Arc<Mutex<T>>, size: BufferSize, frames: usize) -> Self {
+        let buffer = Buffer::init(size);
+
+        Self { app, buffer, frames, frame: 0, dt: 1.0 / 60.0, events: Vec::new() }
+    }
+
+    pub fn with_dt(mut self, dt: f64) -> Self {
+        self.dt = dt;
+        self
+    }
+
+    pub fn send(&mut self, frame: usize, event: Event<'static>) {
+        self.events.push((frame, event));
+    }
+
+    // a single frame press, the key is released on the next frame
+    pub fn press(&mut self, frame: usize, key: Key) {
+        self.send(frame, Event::KeyPressed { key, repeat: false });
+        self.send(frame + 1, Event::KeyReleased { key });
+    }
+
+    // Keys pressed on consecutive frames are held, the later presses are repeats
+    pub fn with_keys(mut self, keys: &[(usize, Key)]) -> Self {
+        for &(frame, key) in keys {
+            let held = |frame: usize| keys.contains(&(frame, key));
+
+            let repeat = frame > 0 && held(frame - 1);
+
+            self.send(frame, Event::KeyPressed { key, repeat });
+
+            if !held(frame + 1) {
+                self.send(frame + 1, Event::KeyReleased { key });
+            }
+        }
+
+        self
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.frames
+    }
+
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    pub fn into_buffer(self) -> Buffer {
+        self.buffer
+    }
+
+    pub fn app(&self) -> &Arc<Mutex<T>> {
+        &self.app
+    }
+
+    pub fn resize(&mut self, size: BufferSize) {
+        self.buffer = Buffer::init(size);
+
+        let mut app = self.app.lock().unwrap();
+
+        app.event(Event::Resized { size });
+        app.event(Event::RedrawReqiest { buffer: &mut self.buffer });
+    }
+
+    // Returns true if the app redrew the buffer during this frame
+    pub fn step(&mut self) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+
+        let mut app = self.app.lock().unwrap();
+
+        if self.frame == 0 {
+            app.event(Event::RedrawReqiest { buffer: &mut self.buffer });
+        }
+
+        // scripted events are sent in the order they were added
+        let (current, later) = std::mem::take(&mut self.events)
+            .into_iter()
+            .partition(|(frame, _)| *frame == self.frame);
+
+        self.events = later;
+
+        for (_, event) in current {
+            app.event(event);
+        }
+
+        app.update(self.dt);
+
+        let redraw = app.need_to_redraw();
+
+        if redraw {
+            app.redrawed();
+            app.event(Event::RedrawReqiest { buffer: &mut self.buffer });
+        }
+
+        self.frame += 1;
+
+        if self.is_finished() {
+            app.event(Event::CloseRequested);
+        }
+
+        redraw
+    }
+
+    pub fn run(&mut self) -> &Buffer {
+        while !self.is_finished() {
+            self.step();
+        }
+
+        &self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use simple_linear_algebra::vector::vec2::Vec2;
+
+    use crate::{color::Color, render::{app_handler::{AppHandler, Event}, buffer::BufferSize, headless::Headless, input::Key}};
+
+    // fills the buffer red once a key was pressed
+    #[derive(Default)]
+    struct Presses {
+        keys: Vec<(Key, bool)>,
+        need_to_redraw: bool
+    }
+
+    impl AppHandler for Presses {
+        fn event(&mut self, event: Event) {
+            match event {
+                Event::KeyPressed { key, repeat } => {
+                    self.keys.push((key, repeat));
+                    self.need_to_redraw = true;
+                }
+
+                Event::RedrawReqiest { buffer } => {
+                    buffer.fill(if self.keys.is_empty() { Color::BLACK } else { Color::RED });
+                }
+
+                _ => ()
+            }
+        }
+
+        fn need_to_redraw(&self) -> bool {
+            self.need_to_redraw
+        }
+
+        fn redrawed(&mut self) {
+            self.need_to_redraw = false;
+        }
+    }
+
+    #[test]
+    fn held_keys() {
+        let app = Arc::new(Mutex::new(Presses::default()));
+
+        let mut headless = Headless::new(app.clone(), BufferSize::new(4, 3), 4)
+            .with_keys(&[(1, Key::A), (2, Key::A)]);
+
+        assert!(!headless.step());
+        assert_eq!(headless.buffer().get_point(Vec2::new(3, 2)), Color::BLACK.0);
+
+        assert!(headless.step());
+        assert_eq!(headless.buffer().get_point(Vec2::new(3, 2)), Color::RED.0);
+
+        headless.run();
+
+        assert!(headless.is_finished());
+        assert_eq!(app.lock().unwrap().keys, vec![(Key::A, false), (Key::A, true)]);
+    }
+}
@@ -1,6 +1,6 @@
-use std::{fs::File, io::BufReader};
+use std::{fs::File, io::{BufRead, BufReader, BufWriter, Seek, Write}, path::Path};
 
-use png::{Decoder, DecodingError};
+use png::{ColorType, Decoder, DecodingError, EncodingError, Transformations};
 
 use crate::render::buffer::{Buffer, BufferSize, RawBuffer};
 
@@ -11,29 +11,182 @@ impl Image {
         Image(buffer)
     }
 
-    // Problems with RGB/RGBA
     pub fn from_png(file: File) -> Result<Self, DecodingError> {
-        let decoder = Decoder::new(BufReader::new(file));
+        Self::parse_png(BufReader::new(file))
+    }
+
+    pub fn parse_png<R: BufRead + Seek>(reader: R) -> Result<Self, DecodingError> {
+        let mut decoder = Decoder::new(reader);
+
+        // palette, low bit depth and tRNS are expanded, 16 bit channels are cut down to 8 bit
+        decoder.set_transformations(Transformations::normalize_to_color8());
+
         let mut reader = decoder.read_info()?;
 
-        let mut buffer = vec![0; reader.output_buffer_size().unwrap()];
+        let buffer_size = reader.output_buffer_size()
+            .ok_or(DecodingError::LimitsExceeded)?;
 
-        let info = reader.next_frame(&mut buffer).unwrap();
+        let mut buffer = vec![0; buffer_size];
 
-        let buffer = Vec::from(&buffer[..info.buffer_size()]);
+        let info = reader.next_frame(&mut buffer)?;
+
+        let buffer = &buffer[..info.buffer_size()];
 
         let size = BufferSize::new(info.width as usize, info.height as usize);
 
-        let raw_buffer = RawBuffer::new(
-            buffer
-            .chunks_exact(3)
-            .map(|chunk| {
-                let (r, g, b) = (chunk[0] as u32, chunk[1] as u32, chunk[2] as u32);
-                (r << 16) | (g << 8) | b
-            })
-            .collect()
-        );
-
-        Ok(Image::new(Buffer::new(raw_buffer, size)))
+        let argb = |r: u8, g: u8, b: u8, a: u8| {
+            ((a as u32) << 24) | ((r as u32) << 16) | ((g as u32) << 8) | b as u32
+        };
+
+        let pixels = match info.color_type {
+            ColorType::Grayscale => buffer
+                .iter()
+                .map(|&l| argb(l, l, l, u8::MAX))
+                .collect(),
+
+            ColorType::GrayscaleAlpha => buffer
+                .chunks_exact(2)
+                .map(|c| argb(c[0], c[0], c[0], c[1]))
+                .collect(),
+
+            ColorType::Rgb => buffer
+                .chunks_exact(3)
+                .map(|c| argb(c[0], c[1], c[2], u8::MAX))
+                .collect(),
+
+            ColorType::Rgba => buffer
+                .chunks_exact(4)
+                .map(|c| argb(c[0], c[1], c[2], c[3]))
+                .collect(),
+
+            // normalize_to_color8 always expands indexed images
+            ColorType::Indexed => unreachable!()
+        };
+
+        Ok(Image::new(Buffer::new(RawBuffer::new(pixels), size)))
+    }
+
+    // unlike `Buffer::to_png` the alpha channel is kept
+    pub fn to_png<W: Write>(&self, writer: W) -> Result<(), EncodingError> {
+        self.0.encode_png(writer, ColorType::Rgba)
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), EncodingError> {
+        let file = File::create(path)?;
+
+        self.to_png(BufWriter::new(file))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use png::{BitDepth, ColorType, Encoder, Info, chunk::ChunkType};
+
+    use crate::{color::Color, render::{buffer::{Buffer, BufferSize, RawBuffer}, image::Image}};
+
+    fn decoded(png: Vec<u8>) -> Vec<u32> {
+        Image::parse_png(Cursor::new(png)).unwrap().0.raw_buffer.0
+    }
+
+    fn encoded(width: u32, height: u32, color: ColorType, depth: BitDepth, setup: impl FnOnce(&mut Encoder<&mut Vec<u8>>), data: &[u8]) -> Vec<u8> {
+        let mut png = Vec::new();
+
+        let mut encoder = Encoder::new(&mut png, width, height);
+        encoder.set_color(color);
+        encoder.set_depth(depth);
+        setup(&mut encoder);
+
+        encoder.write_header().unwrap().write_image_data(data).unwrap();
+
+        png
+    }
+
+    // one stored deflate block, the encoder can not interlace by itself
+    fn zlib_stored(data: &[u8]) -> Vec<u8> {
+        let (mut a, mut b) = (1u32, 0u32);
+
+        for &byte in data {
+            a = (a + byte as u32) % 65521;
+            b = (b + a) % 65521;
+        }
+
+        let len = data.len() as u16;
+
+        let mut zlib = vec![0x78, 0x01, 0x01];
+        zlib.extend(len.to_le_bytes());
+        zlib.extend((!len).to_le_bytes());
+        zlib.extend(data);
+        zlib.extend(((b << 16) | a).to_be_bytes());
+
+        zlib
+    }
+
+    #[test]
+    fn rgba_round_trip() {
+        let pixels = vec![0xFF10_2030, 0x8040_5060, 0x0000_0000, 0x01FF_FFFF, 0xFFFF_FFFF, 0x7F00_FF00];
+        let image = Image::new(Buffer::new(RawBuffer::new(pixels.clone()), BufferSize::new(3, 2)));
+
+        let mut png = Vec::new();
+        image.to_png(&mut png).unwrap();
+
+        assert_eq!(decoded(png), pixels);
+
+        // the buffer is saved without alpha
+        let mut png = Vec::new();
+        image.0.to_png(&mut png).unwrap();
+
+        assert_eq!(decoded(png), pixels.iter().map(|&pixel| Color::new(pixel).0).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn palette_with_transparency() {
+        // the alpha of the entries past the end of tRNS is 255
+        let png = encoded(4, 1, ColorType::Indexed, BitDepth::Two, |encoder| {
+            encoder.set_palette(vec![255, 0, 0, 0, 255, 0, 0, 0, 255, 10, 20, 30]);
+            encoder.set_trns(vec![0, 128]);
+        }, &[0b00_01_10_11]);
+
+        assert_eq!(decoded(png), vec![0x00FF_0000, 0x8000_FF00, 0xFF00_00FF, 0xFF0A_141E]);
+    }
+
+    #[test]
+    fn sixteen_bit() {
+        let png = encoded(2, 1, ColorType::Rgb, BitDepth::Sixteen, |_| (), &[0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xFF, 0xFF, 0x00, 0xFF, 0x80, 0x00]);
+
+        assert_eq!(decoded(png), vec![0xFF12_569A, 0xFFFF_0080]);
+    }
+
+    #[test]
+    fn grey_with_alpha() {
+        let png = encoded(2, 1, ColorType::GrayscaleAlpha, BitDepth::Eight, |_| (), &[0x40, 0x80, 0xFF, 0x00]);
+
+        assert_eq!(decoded(png), vec![0x8040_4040, 0x00FF_FFFF]);
+    }
+
+    #[test]
+    fn interlaced() {
+        let mut png = Vec::new();
+
+        let mut info = Info::with_size(2, 2);
+        info.color_type = ColorType::Rgb;
+        info.bit_depth = BitDepth::Eight;
+        info.interlaced = true;
+
+        let mut writer = Encoder::with_info(&mut png, info).unwrap().write_header().unwrap();
+
+        // Adam7 keeps three of its passes for a 2 by 2 image: the top left pixel,
+        // the top right one and the bottom row, each row starts with no filter
+        let passes = [
+            0, 1, 2, 3,
+            0, 4, 5, 6,
+            0, 7, 8, 9, 10, 11, 12
+        ];
+
+        writer.write_chunk(ChunkType(*b"IDAT"), &zlib_stored(&passes)).unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!(decoded(png), vec![0xFF01_0203, 0xFF04_0506, 0xFF07_0809, 0xFF0A_0B0C]);
     }
 }
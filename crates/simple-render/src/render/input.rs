@@ -0,0 +1,142 @@
+use simple_linear_algebra::vector::vec2::Vec2;
+
+use crate::render::{app_handler::Event, backend::Backend};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Key {
+    Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9,
+
+    A, B, C, D, E, F, G, H, I, J, K, L, M,
+    N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, F13, F14, F15,
+
+    Down, Left, Right, Up,
+
+    Apostrophe, Backquote, Backslash, Comma, Equal, LeftBracket,
+    Minus, Period, RightBracket, Semicolon, Slash,
+
+    Backspace, Delete, End, Enter, Escape, Home, Insert, Menu,
+    PageDown, PageUp, Pause, Space, Tab,
+
+    NumLock, CapsLock, ScrollLock,
+
+    LeftShift, RightShift, LeftCtrl, RightCtrl, LeftAlt, RightAlt, LeftSuper, RightSuper,
+
+    NumPad0, NumPad1, NumPad2, NumPad3, NumPad4,
+    NumPad5, NumPad6, NumPad7, NumPad8, NumPad9,
+    NumPadDot, NumPadSlash, NumPadAsterisk, NumPadMinus, NumPadPlus, NumPadEnter,
+
+    Unknown
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right
+}
+
+impl MouseButton {
+    pub const ALL: [MouseButton; 3] = [MouseButton::Left, MouseButton::Middle, MouseButton::Right];
+}
+
+// Input of the previous frame, turns the polled backend state into events
+#[derive(Clone, Debug, Default)]
+pub struct InputState {
+    keys: Vec<Key>,
+    buttons: Vec<MouseButton>,
+    mouse_pos: Option<Vec2<f64>>
+}
+
+impl InputState {
+    pub fn keys(&self) -> &[Key] {
+        &self.keys
+    }
+
+    pub fn is_key_down(&self, key: Key) -> bool {
+        self.keys.contains(&key)
+    }
+
+    pub fn is_button_down(&self, button: MouseButton) -> bool {
+        self.buttons.contains(&button)
+    }
+
+    pub fn mouse_pos(&self) -> Option<Vec2<f64>> {
+        self.mouse_pos
+    }
+
+    // Held keys are reported every frame, `repeat` is false only for the first one
+    pub fn poll<B: Backend>(&mut self, backend: &B) -> Vec<Event<'static>> {
+        let mut events = Vec::new();
+
+        let keys = backend.keys();
+
+        for &key in &self.keys {
+            if !keys.contains(&key) {
+                events.push(Event::KeyReleased { key });
+            }
+        }
+
+        for &key in &keys {
+            events.push(Event::KeyPressed { key, repeat: self.keys.contains(&key) });
+        }
+
+        self.keys = keys;
+
+        let mouse_pos = backend.mouse_pos();
+
+        if let Some(pos) = mouse_pos.filter(|&pos| Some(pos) != self.mouse_pos) {
+            events.push(Event::MouseMoved { pos });
+        }
+
+        // buttons released outside of the window are reported at the last known position
+        let pos = mouse_pos.or(self.mouse_pos);
+
+        self.mouse_pos = mouse_pos;
+
+        let buttons = backend.mouse_buttons();
+
+        for button in MouseButton::ALL {
+            match (self.buttons.contains(&button), buttons.contains(&button)) {
+                (false, true) => events.push(Event::MouseButtonPressed { button, pos }),
+                (true, false) => events.push(Event::MouseButtonReleased { button, pos }),
+                _ => ()
+            }
+        }
+
+        self.buttons = buttons;
+
+        if let Some(delta) = backend.scroll_wheel() {
+            events.push(Event::MouseWheel { delta });
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simple_linear_algebra::vector::vec2::Vec2;
+
+    use crate::render::{app_handler::Event, backend::fake::FakeBackend, buffer::BufferSize, input::{InputState, MouseButton}};
+
+    #[test]
+    fn release_outside_window() {
+        let mut input = InputState::default();
+
+        let inside = FakeBackend::new(1).with_size(BufferSize::new(10, 10)).with_mouse(Some(Vec2::new(3.0, 4.0)), vec![MouseButton::Left]);
+        input.poll(&inside);
+
+        let outside = FakeBackend::new(1).with_size(BufferSize::new(10, 10));
+        let events = input.poll(&outside);
+
+        let released = events.iter().find_map(|event| match event {
+            Event::MouseButtonReleased { button: MouseButton::Left, pos } => Some(*pos),
+            _ => None
+        });
+
+        assert_eq!(released, Some(Some(Vec2::new(3.0, 4.0))));
+        assert_eq!(input.mouse_pos(), None);
+    }
+}
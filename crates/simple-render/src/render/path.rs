@@ -0,0 +1,412 @@
+use std::f64::consts::TAU;
+
+use simple_linear_algebra::vector::{Vector, vec2::Vec2};
+
+use crate::{color::Color, render::{buffer::Buffer, shape::{FillRule, arc_points}, stroke::LineStyle}};
+
+// deeper subdivision than this stops even if the curve is still not flat enough
+const MAX_CUBIC_DEPTH: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PathCommand {
+    MoveTo(Vec2<f64>),
+    LineTo(Vec2<f64>),
+    QuadTo { control: Vec2<f64>, to: Vec2<f64> },
+    CubicTo { first: Vec2<f64>, second: Vec2<f64>, to: Vec2<f64> },
+    // an SVG elliptical arc, the rotation of the x radius is in radians
+    ArcTo { radii: Vec2<f64>, rotation: f64, large_arc: bool, sweep: bool, to: Vec2<f64> },
+    Close
+}
+
+// A flattened subpath
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contour {
+    pub points: Vec<Vec2<f64>>,
+    pub closed: bool
+}
+
+// Outline made of lines and curves, built like an SVG path. Every `move_to` starts a new subpath,
+// drawing commands without one start at the end of the previous subpath
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path {
+    commands: Vec<PathCommand>,
+    // the furthest the flattened lines may be from the curves, in pixels
+    tolerance: f64
+}
+
+impl Path {
+    pub const fn new() -> Self {
+        Self { commands: Vec::new(), tolerance: 0.1 }
+    }
+
+    pub const fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn move_to(mut self, to: Vec2<f64>) -> Self {
+        self.commands.push(PathCommand::MoveTo(to));
+        self
+    }
+
+    pub fn line_to(mut self, to: Vec2<f64>) -> Self {
+        self.commands.push(PathCommand::LineTo(to));
+        self
+    }
+
+    pub fn quad_to(mut self, control: Vec2<f64>, to: Vec2<f64>) -> Self {
+        self.commands.push(PathCommand::QuadTo { control, to });
+        self
+    }
+
+    pub fn cubic_to(mut self, first: Vec2<f64>, second: Vec2<f64>, to: Vec2<f64>) -> Self {
+        self.commands.push(PathCommand::CubicTo { first, second, to });
+        self
+    }
+
+    // Of the four arcs of the ellipse through both points, `large_arc` picks one longer than half the ellipse
+    // and `sweep` one going clockwise on the screen. The radii grow if the ellipse is too small to reach
+    pub fn arc_to(mut self, radii: Vec2<f64>, rotation: f64, large_arc: bool, sweep: bool, to: Vec2<f64>) -> Self {
+        self.commands.push(PathCommand::ArcTo { radii, rotation, large_arc, sweep, to });
+        self
+    }
+
+    pub fn close(mut self) -> Self {
+        self.commands.push(PathCommand::Close);
+        self
+    }
+
+    pub fn commands(&self) -> &[PathCommand] {
+        &self.commands
+    }
+
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    // the subpaths as polylines within the tolerance of the curves
+    pub fn flatten(&self) -> Vec<Contour> {
+        let mut contours = Vec::new();
+        let mut points: Vec<Vec2<f64>> = Vec::new();
+
+        // where the current subpath started, and the pen position
+        let mut start = Vec2::new(0.0, 0.0);
+        let mut current = Vec2::new(0.0, 0.0);
+
+        let finish = |points: &mut Vec<Vec2<f64>>, contours: &mut Vec<Contour>, closed: bool| {
+            if points.len() > 1 || (closed && !points.is_empty()) {
+                contours.push(Contour { points: std::mem::take(points), closed });
+            }
+
+            points.clear();
+        };
+
+        for &command in &self.commands {
+            if points.is_empty() && !matches!(command, PathCommand::MoveTo(_) | PathCommand::Close) {
+                points.push(current);
+                start = current;
+            }
+
+            match command {
+                PathCommand::MoveTo(to) => {
+                    finish(&mut points, &mut contours, false);
+
+                    points.push(to);
+                    start = to;
+                    current = to;
+
+                    continue;
+                },
+                PathCommand::LineTo(to) => points.push(to),
+                PathCommand::QuadTo { control, to } => flatten_quad(&mut points, current, control, to, self.tolerance),
+                PathCommand::CubicTo { first, second, to } => {
+                    flatten_cubic(&mut points, [current, first, second, to], self.tolerance, 0);
+                },
+                PathCommand::ArcTo { .. } => flatten_arc(&mut points, current, command, self.tolerance),
+                PathCommand::Close => {
+                    finish(&mut points, &mut contours, true);
+
+                    current = start;
+
+                    continue;
+                }
+            }
+
+            current = *points.last().unwrap();
+        }
+
+        finish(&mut points, &mut contours, false);
+
+        contours
+    }
+}
+
+impl Default for Path {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Enough segments for the tolerance, the second derivative of a quadratic curve is constant, so a segment
+// of 1 / n of it is at most |start - 2 control + end| / (4 n^2) from its chord
+fn flatten_quad(points: &mut Vec<Vec2<f64>>, start: Vec2<f64>, control: Vec2<f64>, end: Vec2<f64>, tolerance: f64) {
+    let deviation = (start - control * 2.0 + end).length();
+    let segments = ((deviation / (4.0 * tolerance)).sqrt().ceil() as usize).clamp(1, 1024);
+
+    for index in 1..=segments {
+        let t = index as f64 / segments as f64;
+
+        points.push(start * ((1.0 - t) * (1.0 - t)) + control * (2.0 * t * (1.0 - t)) + end * (t * t));
+    }
+}
+
+// Halved until the control points are within the tolerance of the chord, flat parts need few points
+fn flatten_cubic(points: &mut Vec<Vec2<f64>>, curve: [Vec2<f64>; 4], tolerance: f64, depth: usize) {
+    let [start, first, second, end] = curve;
+
+    let chord = end - start;
+    let len = chord.length();
+
+    let distance = |point: Vec2<f64>| if len == 0.0 {
+        (point - start).length()
+    } else {
+        (chord.cross(point - start) / len).abs()
+    };
+
+    if depth >= MAX_CUBIC_DEPTH || distance(first).max(distance(second)) <= tolerance {
+        points.push(end);
+        return;
+    }
+
+    // de Casteljau at t = 0.5
+    let ab = (start + first) * 0.5;
+    let bc = (first + second) * 0.5;
+    let cd = (second + end) * 0.5;
+    let abc = (ab + bc) * 0.5;
+    let bcd = (bc + cd) * 0.5;
+    let middle = (abc + bcd) * 0.5;
+
+    flatten_cubic(points, [start, ab, abc, middle], tolerance, depth + 1);
+    flatten_cubic(points, [middle, bcd, cd, end], tolerance, depth + 1);
+}
+
+// the endpoint to center conversion from the SVG implementation notes
+fn flatten_arc(points: &mut Vec<Vec2<f64>>, start: Vec2<f64>, arc: PathCommand, tolerance: f64) {
+    let PathCommand::ArcTo { radii, rotation, large_arc, sweep, to: end } = arc else {
+        return;
+    };
+
+    let (mut rx, mut ry) = (radii.x.abs(), radii.y.abs());
+
+    if start == end {
+        return;
+    }
+
+    if rx == 0.0 || ry == 0.0 {
+        points.push(end);
+        return;
+    }
+
+    let (sin, cos) = rotation.sin_cos();
+
+    let rotate = |v: Vec2<f64>| Vec2::new(cos * v.x - sin * v.y, sin * v.x + cos * v.y);
+    let unrotate = |v: Vec2<f64>| Vec2::new(cos * v.x + sin * v.y, -sin * v.x + cos * v.y);
+
+    // the start point in the frame of the ellipse, relative to the middle of the chord
+    let p = unrotate((start - end) * 0.5);
+
+    let scale = (p.x * p.x) / (rx * rx) + (p.y * p.y) / (ry * ry);
+
+    if scale > 1.0 {
+        rx *= scale.sqrt();
+        ry *= scale.sqrt();
+    }
+
+    let numerator = rx * rx * ry * ry - rx * rx * p.y * p.y - ry * ry * p.x * p.x;
+    let denominator = rx * rx * p.y * p.y + ry * ry * p.x * p.x;
+
+    let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+    let factor = sign * (numerator / denominator).max(0.0).sqrt();
+
+    let center = Vec2::new(factor * rx * p.y / ry, -factor * ry * p.x / rx);
+
+    let angle = |v: Vec2<f64>| v.y.atan2(v.x);
+
+    let from = angle(Vec2::new((p.x - center.x) / rx, (p.y - center.y) / ry));
+    let to = angle(Vec2::new((-p.x - center.x) / rx, (-p.y - center.y) / ry));
+
+    let mut delta = (to - from) % TAU;
+
+    if sweep && delta < 0.0 {
+        delta += TAU;
+    } else if !sweep && delta > 0.0 {
+        delta -= TAU;
+    }
+
+    let middle = (start + end) * 0.5;
+    let arc = arc_points(Vec2::new(0.0, 0.0), Vec2::new(rx, ry), from, from + delta, tolerance);
+
+    // the first point is the start, the last one is replaced by the exact end
+    points.extend(arc[1..arc.len() - 1].iter().map(|&point| rotate(point + center) + middle));
+    points.push(end);
+}
+
+impl Buffer {
+    // open subpaths get caps, closed ones are joined all around
+    pub fn draw_path(&mut self, path: &Path, style: &LineStyle, color: Color) {
+        for contour in path.flatten() {
+            if contour.closed {
+                self.draw_polygon(&contour.points, style, color);
+            } else {
+                self.draw_polyline(&contour.points, style, color);
+            }
+        }
+    }
+
+    // every subpath is closed for filling, together they make one shape
+    pub fn fill_path(&mut self, path: &Path, rule: FillRule, color: Color) {
+        let contours: Vec<Vec<Vec2<f64>>> = path.flatten()
+            .into_iter()
+            .map(|contour| contour.points)
+            .collect();
+
+        self.fill_contours(&contours, rule, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simple_linear_algebra::vector::{Vector, vec2::Vec2};
+
+    use crate::{color::Color, render::{buffer::{Buffer, BufferSize}, path::{Contour, Path}, shape::FillRule}};
+
+    fn distance_to_polyline(point: Vec2<f64>, points: &[Vec2<f64>]) -> f64 {
+        points.windows(2)
+            .map(|segment| {
+                let (start, end) = (segment[0], segment[1]);
+                let along = end - start;
+                let t = ((point - start).dot(along) / along.dot(along)).clamp(0.0, 1.0);
+
+                (start + along * t - point).length()
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    // every sample of the curve is within the tolerance of the flattened line, which ends exactly at `end`
+    fn check_flattening(path: &Path, curve: impl Fn(f64) -> Vec2<f64>, end: Vec2<f64>) -> usize {
+        let contours = path.flatten();
+        assert_eq!(contours.len(), 1);
+
+        let points = &contours[0].points;
+
+        assert_eq!(points.last(), Some(&end));
+
+        for index in 0..=1000 {
+            let point = curve(index as f64 / 1000.0);
+            let distance = distance_to_polyline(point, points);
+
+            assert!(distance <= path.tolerance() + 1e-9, "{point:?} is {distance} away");
+        }
+
+        points.len()
+    }
+
+    #[test]
+    fn flatten_within_tolerance() {
+        let (start, control, end) = (Vec2::new(0.0, 0.0), Vec2::new(50.0, 100.0), Vec2::new(100.0, 0.0));
+        let quad = |t: f64| start * ((1.0 - t) * (1.0 - t)) + control * (2.0 * t * (1.0 - t)) + end * (t * t);
+
+        let coarse = check_flattening(&Path::new().with_tolerance(1.0).move_to(start).quad_to(control, end), quad, end);
+        let fine = check_flattening(&Path::new().with_tolerance(0.05).move_to(start).quad_to(control, end), quad, end);
+
+        assert!(fine > coarse, "{fine} {coarse}");
+
+        let (first, second) = (Vec2::new(0.0, 80.0), Vec2::new(100.0, -80.0));
+        let cubic = |t: f64| {
+            let s = 1.0 - t;
+            start * (s * s * s) + first * (3.0 * s * s * t) + second * (3.0 * s * t * t) + end * (t * t * t)
+        };
+
+        check_flattening(&Path::new().move_to(start).cubic_to(first, second, end), cubic, end);
+
+        // a half circle, clockwise on the screen goes up from the start
+        let arc = |t: f64| {
+            let angle = std::f64::consts::PI * (1.0 + t);
+            Vec2::new(50.0 + 50.0 * angle.cos(), 50.0 * angle.sin())
+        };
+
+        check_flattening(&Path::new().with_tolerance(0.2).move_to(start).arc_to(Vec2::new(50.0, 50.0), 0.0, false, true, end), arc, end);
+    }
+
+    #[test]
+    fn subpaths() {
+        let (a, b, c, d) = (Vec2::new(10.0, 10.0), Vec2::new(20.0, 10.0), Vec2::new(20.0, 20.0), Vec2::new(30.0, 30.0));
+
+        // close goes back to the start, the next line starts there
+        let closed = Path::new().move_to(a).line_to(b).line_to(c).close().line_to(d).flatten();
+
+        assert_eq!(closed, vec![
+            Contour { points: vec![a, b, c], closed: true },
+            Contour { points: vec![a, d], closed: false }
+        ]);
+
+        // every move starts a new contour, a move with nothing after it is dropped
+        let moved = Path::new().move_to(a).line_to(b).move_to(c).line_to(d).move_to(a).flatten();
+
+        assert_eq!(moved, vec![
+            Contour { points: vec![a, b], closed: false },
+            Contour { points: vec![c, d], closed: false }
+        ]);
+
+        // without a move the path starts at the origin
+        assert_eq!(Path::new().line_to(a).flatten(), vec![Contour { points: vec![Vec2::new(0.0, 0.0), a], closed: false }]);
+    }
+
+    #[test]
+    fn degenerate_arcs() {
+        let (a, b) = (Vec2::new(10.0, 10.0), Vec2::new(20.0, 10.0));
+
+        // a zero radius makes a straight line
+        let straight = Path::new().move_to(a).arc_to(Vec2::new(0.0, 5.0), 0.0, false, true, b).flatten();
+        assert_eq!(straight, vec![Contour { points: vec![a, b], closed: false }]);
+
+        // an arc to where it starts draws nothing
+        assert!(Path::new().move_to(a).arc_to(Vec2::new(5.0, 5.0), 0.0, true, true, a).flatten().is_empty());
+
+        // radii too small to reach are scaled up to a half ellipse
+        let grown = Path::new().move_to(a).arc_to(Vec2::new(1.0, 1.0), 0.0, false, false, b).flatten();
+        let center = (a + b) * 0.5;
+
+        assert!(grown[0].points.iter().all(|&point| ((point - center).length() - 5.0).abs() < 1e-9 && point.y >= 10.0 - 1e-9));
+    }
+
+    #[test]
+    fn fill_rules() {
+        // two circles winding the same way, the inner one is a hole only for even-odd
+        let circle = |path: Path, radius: f64| path
+            .move_to(Vec2::new(10.0 - radius, 10.0))
+            .arc_to(Vec2::new(radius, radius), 0.0, false, true, Vec2::new(10.0 + radius, 10.0))
+            .arc_to(Vec2::new(radius, radius), 0.0, false, true, Vec2::new(10.0 - radius, 10.0))
+            .close();
+
+        let rings = circle(circle(Path::new(), 8.0), 3.0);
+
+        for rule in [FillRule::NonZero, FillRule::EvenOdd] {
+            let mut buffer = Buffer::init(BufferSize::new(20, 20));
+
+            buffer.fill(Color::BLACK);
+            buffer.fill_path(&rings, rule, Color::WHITE);
+
+            assert_eq!(buffer.get_point(Vec2::new(4, 9)), Color::WHITE.0, "{rule:?}");
+            assert_eq!(buffer.get_point(Vec2::new(0, 0)), Color::BLACK.0, "{rule:?}");
+            assert_eq!(buffer.get_point(Vec2::new(19, 19)), Color::BLACK.0, "{rule:?}");
+
+            let center = if rule == FillRule::NonZero { Color::WHITE } else { Color::BLACK };
+            assert_eq!(buffer.get_point(Vec2::new(9, 9)), center.0, "{rule:?}");
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use simple_linear_algebra::vector::{Vector, vec2::Vec2};
+
+// Anti-aliased coverage of closed outlines. Every line adds the signed area it covers to the cells it crosses,
+// the running sum of a row then gives the coverage of each pixel. Overlapping contours of the same direction
+// saturate, so the result follows the non-zero rule
+#[derive(Clone, Debug)]
+pub struct Rasterizer {
+    width: usize,
+    height: usize,
+    // two extra cells take what spills over the right edge of the last row
+    accumulation: Vec<f64>
+}
+
+impl Rasterizer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height, accumulation: vec![0.0; width * height + 2] }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // Points are in pixels with y down. Parts left or right of the bitmap are moved onto its edge,
+    // which keeps the coverage of the rest correct
+    pub fn line(&mut self, start: Vec2<f64>, end: Vec2<f64>) {
+        if start.y == end.y {
+            return;
+        }
+
+        let (direction, top, bottom) = if start.y < end.y {
+            (1.0, start, end)
+        } else {
+            (-1.0, end, start)
+        };
+
+        let right = self.width as f64;
+
+        let dxdy = (bottom.x - top.x) / (bottom.y - top.y);
+
+        let mut x = top.x;
+
+        if top.y < 0.0 {
+            x -= top.y * dxdy;
+        }
+
+        let first = top.y.max(0.0) as usize;
+        let last = (bottom.y.ceil().max(0.0) as usize).min(self.height);
+
+        for y in first..last {
+            let row = y * self.width;
+
+            let dy = ((y + 1) as f64).min(bottom.y) - (y as f64).max(top.y);
+            let next_x = x + dxdy * dy;
+            let area = dy * direction;
+
+            let (row_start, row_end) = (x.clamp(0.0, right), next_x.clamp(0.0, right));
+            let (x0, x1) = if row_start < row_end { (row_start, row_end) } else { (row_end, row_start) };
+
+            let x0_floor = x0.floor();
+            let x1_ceil = x1.ceil();
+
+            let (x0_cell, x1_cell) = (x0_floor as usize, x1_ceil as usize);
+
+            if x1_cell <= x0_cell + 1 {
+                // the line stays inside one cell, the part right of its middle goes to the next cell
+                let middle = 0.5 * (row_start + row_end) - x0_floor;
+
+                self.accumulation[row + x0_cell] += area * (1.0 - middle);
+                self.accumulation[row + x0_cell + 1] += area * middle;
+            } else {
+                let inverse_width = 1.0 / (x1 - x0);
+
+                let x0_fraction = x0 - x0_floor;
+                let first_area = 0.5 * inverse_width * (1.0 - x0_fraction) * (1.0 - x0_fraction);
+
+                let x1_fraction = x1 - x1_ceil + 1.0;
+                let last_area = 0.5 * inverse_width * x1_fraction * x1_fraction;
+
+                self.accumulation[row + x0_cell] += area * first_area;
+
+                if x1_cell == x0_cell + 2 {
+                    self.accumulation[row + x0_cell + 1] += area * (1.0 - first_area - last_area);
+                } else {
+                    let second_area = inverse_width * (1.5 - x0_fraction);
+
+                    self.accumulation[row + x0_cell + 1] += area * (second_area - first_area);
+
+                    for cell in x0_cell + 2..x1_cell - 1 {
+                        self.accumulation[row + cell] += area * inverse_width;
+                    }
+
+                    let before_last = second_area + (x1_cell - x0_cell - 3) as f64 * inverse_width;
+
+                    self.accumulation[row + x1_cell - 1] += area * (1.0 - before_last - last_area);
+                }
+
+                self.accumulation[row + x1_cell] += area * last_area;
+            }
+
+            x = next_x;
+        }
+    }
+
+    // flattened into lines until they are within `tolerance` pixels of the curve
+    pub fn quad(&mut self, start: Vec2<f64>, control: Vec2<f64>, end: Vec2<f64>, tolerance: f64) {
+        let deviation = (start - control * 2.0 + end).length();
+
+        let segments = ((deviation / (8.0 * tolerance)).sqrt().ceil() as usize).max(1);
+
+        let mut previous = start;
+
+        for index in 1..=segments {
+            let t = index as f64 / segments as f64;
+            let point = start * ((1.0 - t) * (1.0 - t)) + control * (2.0 * t * (1.0 - t)) + end * (t * t);
+
+            self.line(previous, point);
+            previous = point;
+        }
+    }
+
+    // row-major, 0 is uncovered and 255 fully covered
+    pub fn coverage(&self) -> Vec<u8> {
+        let mut sum = 0.0;
+
+        self.accumulation[..self.width * self.height]
+            .iter()
+            .map(|cell| {
+                sum += cell;
+                (sum.abs().min(1.0) * 255.0).round() as u8
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simple_linear_algebra::vector::vec2::Vec2;
+
+    use crate::render::raster::Rasterizer;
+
+    fn square(rasterizer: &mut Rasterizer, corner: Vec2<f64>, clockwise: bool) {
+        let mut points = [corner, corner + Vec2::new(1.0, 0.0), corner + Vec2::new(1.0, 1.0), corner + Vec2::new(0.0, 1.0)];
+
+        if !clockwise {
+            points.reverse();
+        }
+
+        for index in 0..points.len() {
+            rasterizer.line(points[index], points[(index + 1) % points.len()]);
+        }
+    }
+
+    #[test]
+    fn unit_square() {
+        for clockwise in [true, false] {
+            let mut rasterizer = Rasterizer::new(4, 3);
+            square(&mut rasterizer, Vec2::new(1.0, 1.0), clockwise);
+
+            let mut expected = vec![0; 12];
+            expected[5] = 255;
+
+            assert_eq!(rasterizer.coverage(), expected);
+
+            // off the pixel grid each of the four pixels gets a quarter
+            let mut rasterizer = Rasterizer::new(4, 3);
+            square(&mut rasterizer, Vec2::new(0.5, 0.5), clockwise);
+
+            assert_eq!(rasterizer.coverage(), vec![64, 64, 0, 0, 64, 64, 0, 0, 0, 0, 0, 0]);
+        }
+    }
+}
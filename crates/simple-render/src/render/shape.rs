@@ -0,0 +1,338 @@
+use std::f64::consts::TAU;
+
+use simple_linear_algebra::vector::vec2::Vec2;
+
+use crate::{color::Color, render::{buffer::Buffer, stroke::LineStyle}};
+
+// how far the polygons of curved shapes may be off the curve, in pixels
+const CURVE_TOLERANCE: f64 = 0.1;
+// scanlines per pixel row, the coverage along a scanline is exact
+const SUBSCANLINES: usize = 4;
+
+// Which parts of a self-intersecting polygon are inside
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FillRule {
+    // inside where the outline winds around the point at least once in either direction
+    #[default]
+    NonZero,
+    // inside where a ray from the point crosses the outline an odd number of times
+    EvenOdd
+}
+
+// Points of an elliptic arc from `start` to `end`, both included. Angles are in radians from the +x axis,
+// with y pointing down positive angles turn clockwise on the screen
+pub(crate) fn arc_points(center: Vec2<f64>, radii: Vec2<f64>, start: f64, end: f64, tolerance: f64) -> Vec<Vec2<f64>> {
+    let radius = radii.x.abs().max(radii.y.abs());
+
+    // the angle one segment may span so its middle stays within the tolerance
+    let step = 2.0 * (1.0 - tolerance / radius).clamp(-1.0, 1.0).acos();
+    let sweep = end - start;
+
+    let count = ((sweep.abs() / step).ceil() as usize).clamp(2, 1024);
+
+    (0..=count)
+        .map(|index| {
+            let angle = start + sweep * index as f64 / count as f64;
+            center + Vec2::new(angle.cos() * radii.x, angle.sin() * radii.y)
+        })
+        .collect()
+}
+
+fn rounded_rectangle_points(rectangle: Vec2<Vec2<f64>>, radius: f64) -> Vec<Vec2<f64>> {
+    let Vec2 { x: min, y: max } = rectangle;
+
+    let radius = radius.max(0.0).min((max.x - min.x) / 2.0).min((max.y - min.y) / 2.0);
+
+    if radius == 0.0 {
+        return vec![min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)];
+    }
+
+    let quarter = TAU / 4.0;
+    let radii = Vec2::splat(radius);
+
+    // clockwise on the screen from the top left corner
+    [
+        (Vec2::new(min.x + radius, min.y + radius), 2.0 * quarter),
+        (Vec2::new(max.x - radius, min.y + radius), 3.0 * quarter),
+        (Vec2::new(max.x - radius, max.y - radius), 0.0),
+        (Vec2::new(min.x + radius, max.y - radius), quarter)
+    ]
+        .into_iter()
+        .flat_map(|(center, start)| arc_points(center, radii, start, start + quarter, CURVE_TOLERANCE))
+        .collect()
+}
+
+// an edge of the polygon going down, `winding` is -1 if it went up originally
+struct Edge {
+    top: Vec2<f64>,
+    bottom: Vec2<f64>,
+    winding: i32
+}
+
+impl Edge {
+    fn x_at(&self, y: f64) -> f64 {
+        self.top.x + (y - self.top.y) * (self.bottom.x - self.top.x) / (self.bottom.y - self.top.y)
+    }
+}
+
+impl Buffer {
+    // Scanline fill of any polygon, concave and self-intersecting ones included. The edges are anti-aliased
+    // with several scanlines per row. Pixel (x, y) covers [x, x + 1) x [y, y + 1)
+    pub fn fill_polygon(&mut self, points: &[Vec2<f64>], rule: FillRule, color: Color) {
+        self.fill_contours(&[points], rule, color);
+    }
+
+    // fills several closed contours as one shape, so holes and overlaps follow the fill rule
+    pub(crate) fn fill_contours<P: AsRef<[Vec2<f64>]>>(&mut self, contours: &[P], rule: FillRule, color: Color) {
+        let points = || contours.iter().flat_map(|contour| contour.as_ref());
+
+        if points().any(|point| !point.x.is_finite() || !point.y.is_finite()) {
+            return;
+        }
+
+        let mut edges: Vec<Edge> = contours.iter()
+            .flat_map(|contour| {
+                let contour = contour.as_ref();
+                contour.iter().zip(contour.iter().cycle().skip(1))
+            })
+            .filter(|(start, end)| start.y != end.y)
+            .map(|(&start, &end)| match start.y < end.y {
+                true => Edge { top: start, bottom: end, winding: 1 },
+                false => Edge { top: end, bottom: start, winding: -1 }
+            })
+            .collect();
+
+        if edges.is_empty() {
+            return;
+        }
+
+        edges.sort_by(|a, b| a.top.y.total_cmp(&b.top.y));
+
+        let top = edges[0].top.y.floor().max(0.0) as usize;
+        let bottom = edges.iter()
+            .map(|edge| edge.bottom.y)
+            .fold(f64::NEG_INFINITY, f64::max)
+            .ceil()
+            .clamp(0.0, self.size.height as f64) as usize;
+
+        let width = self.size.width;
+
+        // edges are added once the scanline reaches them and dropped after it passes
+        let mut next_edge = 0;
+        let mut active: Vec<&Edge> = Vec::new();
+
+        let mut coverage = vec![0.0; width + 1];
+        let mut crossings: Vec<(f64, i32)> = Vec::new();
+
+        for row in top..bottom {
+            coverage.fill(0.0);
+
+            for sub in 0..SUBSCANLINES {
+                let y = row as f64 + (sub as f64 + 0.5) / SUBSCANLINES as f64;
+
+                while next_edge < edges.len() && edges[next_edge].top.y <= y {
+                    active.push(&edges[next_edge]);
+                    next_edge += 1;
+                }
+
+                active.retain(|edge| edge.bottom.y > y);
+
+                crossings.clear();
+                crossings.extend(active.iter().map(|edge| (edge.x_at(y), edge.winding)));
+                crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+                let mut winding = 0;
+
+                for pair in crossings.windows(2) {
+                    winding += match rule {
+                        FillRule::NonZero => pair[0].1,
+                        FillRule::EvenOdd => 1
+                    };
+
+                    let inside = match rule {
+                        FillRule::NonZero => winding != 0,
+                        FillRule::EvenOdd => winding % 2 == 1
+                    };
+
+                    if inside {
+                        add_span(&mut coverage, pair[0].0, pair[1].0, 1.0 / SUBSCANLINES as f64);
+                    }
+                }
+            }
+
+            for (x, &covered) in coverage[..width].iter().enumerate() {
+                let covered = (covered.min(1.0) * 255.0).round() as u8;
+
+                if covered != 0 {
+                    self.raw_draw_point(Vec2::new(x as isize, row as isize), color.fade(covered));
+                }
+            }
+        }
+    }
+
+    pub fn draw_circle(&mut self, center: Vec2<f64>, radius: f64, style: &LineStyle, color: Color) {
+        self.draw_ellipse(center, Vec2::splat(radius), style, color);
+    }
+
+    pub fn fill_circle(&mut self, center: Vec2<f64>, radius: f64, color: Color) {
+        self.fill_ellipse(center, Vec2::splat(radius), color);
+    }
+
+    pub fn draw_ellipse(&mut self, center: Vec2<f64>, radii: Vec2<f64>, style: &LineStyle, color: Color) {
+        let mut points = arc_points(center, radii, 0.0, TAU, CURVE_TOLERANCE);
+        points.pop();
+
+        self.draw_polygon(&points, style, color);
+    }
+
+    pub fn fill_ellipse(&mut self, center: Vec2<f64>, radii: Vec2<f64>, color: Color) {
+        let points = arc_points(center, radii, 0.0, TAU, CURVE_TOLERANCE);
+
+        self.fill_polygon(&points, FillRule::NonZero, color);
+    }
+
+    // Angles are in radians from the +x axis and turn clockwise on the screen, the arc goes from `start` to `end`
+    pub fn draw_arc(&mut self, center: Vec2<f64>, radius: f64, start: f64, end: f64, style: &LineStyle, color: Color) {
+        let points = arc_points(center, Vec2::splat(radius), start, end, CURVE_TOLERANCE);
+
+        self.draw_polyline(&points, style, color);
+    }
+
+    // the outline of a pie slice, the arc and both radii
+    pub fn draw_pie(&mut self, center: Vec2<f64>, radius: f64, start: f64, end: f64, style: &LineStyle, color: Color) {
+        let mut points = arc_points(center, Vec2::splat(radius), start, end, CURVE_TOLERANCE);
+        points.push(center);
+
+        self.draw_polygon(&points, style, color);
+    }
+
+    pub fn fill_pie(&mut self, center: Vec2<f64>, radius: f64, start: f64, end: f64, color: Color) {
+        let mut points = arc_points(center, Vec2::splat(radius), start, end, CURVE_TOLERANCE);
+        points.push(center);
+
+        self.fill_polygon(&points, FillRule::NonZero, color);
+    }
+
+    // the corner radius is limited to half the shorter side
+    pub fn draw_rounded_rectangle(&mut self, rectangle: Vec2<Vec2<f64>>, radius: f64, style: &LineStyle, color: Color) {
+        self.draw_polygon(&rounded_rectangle_points(rectangle, radius), style, color);
+    }
+
+    pub fn fill_rounded_rectangle(&mut self, rectangle: Vec2<Vec2<f64>>, radius: f64, color: Color) {
+        self.fill_polygon(&rounded_rectangle_points(rectangle, radius), FillRule::NonZero, color);
+    }
+}
+
+// adds `weight` times the part of every pixel that [start, end) covers
+fn add_span(coverage: &mut [f64], start: f64, end: f64, weight: f64) {
+    let right = (coverage.len() - 1) as f64;
+
+    let (start, end) = (start.clamp(0.0, right), end.clamp(0.0, right));
+
+    if start >= end {
+        return;
+    }
+
+    let (first, last) = (start.floor() as usize, end.floor() as usize);
+
+    if first == last {
+        coverage[first] += (end - start) * weight;
+        return;
+    }
+
+    coverage[first] += (first as f64 + 1.0 - start) * weight;
+
+    for cell in &mut coverage[first + 1..last] {
+        *cell += weight;
+    }
+
+    coverage[last] += (end - last as f64) * weight;
+}
+
+#[cfg(test)]
+mod tests {
+    use simple_linear_algebra::vector::vec2::Vec2;
+
+    use crate::{color::Color, render::{buffer::{Buffer, BufferSize}, shape::{FillRule, add_span}}};
+
+    fn square(min: f64, max: f64, clockwise: bool) -> Vec<Vec2<f64>> {
+        let mut points = vec![Vec2::new(min, min), Vec2::new(max, min), Vec2::new(max, max), Vec2::new(min, max)];
+
+        if !clockwise {
+            points.reverse();
+        }
+
+        points
+    }
+
+    fn filled(contours: &[Vec<Vec2<f64>>], rule: FillRule) -> Buffer {
+        let mut buffer = Buffer::init(BufferSize::new(20, 20));
+
+        buffer.fill(Color::BLACK);
+        buffer.fill_contours(contours, rule, Color::WHITE);
+
+        buffer
+    }
+
+    fn is_filled(buffer: &Buffer, x: isize, y: isize) -> bool {
+        buffer.get_point(Vec2::new(x, y)) == Color::WHITE.0
+    }
+
+    #[test]
+    fn spans() {
+        let mut coverage = vec![0.0; 5];
+
+        add_span(&mut coverage, 0.5, 2.25, 1.0);
+        assert_eq!(coverage, vec![0.5, 1.0, 0.25, 0.0, 0.0]);
+
+        add_span(&mut coverage, 1.25, 1.75, 0.5);
+        assert_eq!(coverage, vec![0.5, 1.25, 0.25, 0.0, 0.0]);
+
+        // clamped to the row, the extra cell is never reached
+        let mut coverage = vec![0.0; 5];
+
+        add_span(&mut coverage, -3.0, 10.0, 0.25);
+        add_span(&mut coverage, 3.0, 2.0, 1.0);
+        assert_eq!(coverage, vec![0.25, 0.25, 0.25, 0.25, 0.0]);
+    }
+
+    #[test]
+    fn nested_contours() {
+        for rule in [FillRule::NonZero, FillRule::EvenOdd] {
+            // a hole that winds the other way is a hole under both rules
+            let buffer = filled(&[square(2.0, 14.0, true), square(6.0, 10.0, false)], rule);
+
+            assert!(is_filled(&buffer, 3, 3));
+            assert!(!is_filled(&buffer, 8, 8));
+            assert_eq!(buffer.get_point(Vec2::new(15, 15)), Color::BLACK.0);
+
+            let buffer = filled(&[square(2.0, 14.0, true), square(6.0, 10.0, true)], rule);
+
+            assert!(is_filled(&buffer, 3, 3));
+            assert_eq!(is_filled(&buffer, 8, 8), rule == FillRule::NonZero, "{rule:?}");
+        }
+    }
+
+    #[test]
+    fn self_intersecting() {
+        // a five-pointed star drawn in one stroke winds twice around its middle
+        let star = (0..5)
+            .map(|index| {
+                let angle = (-90.0 + 144.0 * index as f64).to_radians();
+                Vec2::new(10.0 + 9.0 * angle.cos(), 10.0 + 9.0 * angle.sin())
+            })
+            .collect::<Vec<_>>();
+
+        let non_zero = filled(std::slice::from_ref(&star), FillRule::NonZero);
+        let even_odd = filled(&[star], FillRule::EvenOdd);
+
+        assert!(is_filled(&non_zero, 10, 10));
+        assert_eq!(even_odd.get_point(Vec2::new(10, 10)), Color::BLACK.0);
+
+        // the points are inside under both rules
+        for buffer in [&non_zero, &even_odd] {
+            assert_ne!(buffer.get_point(Vec2::new(10, 4)), Color::BLACK.0);
+            assert_eq!(buffer.get_point(Vec2::new(1, 18)), Color::BLACK.0);
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::VecDeque;
+
+use simple_linear_algebra::vector::vec2::Vec2;
+
+use crate::{color::Color, render::{buffer::Buffer, font::BitmapFont}};
+
+// durations of the parts of one `Render::run` frame, in seconds
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameTimings {
+    pub events: f64,
+    pub update: f64,
+    pub redraw: f64,
+    pub present: f64,
+    // the whole frame including the pacing sleep
+    pub frame: f64
+}
+
+impl FrameTimings {
+    // time actually spent working, without the sleep
+    pub fn busy(&self) -> f64 {
+        self.events + self.update + self.redraw + self.present
+    }
+}
+
+// Timings of the last frames, the oldest are dropped once the capacity is reached
+#[derive(Clone, Debug)]
+pub struct FrameStats {
+    history: VecDeque<FrameTimings>,
+    capacity: usize
+}
+
+const OVERLAY_POS: Vec2<isize> = Vec2::new(8, 8);
+const OVERLAY_GRAPH_HEIGHT: isize = 64;
+const OVERLAY_TEXT_SCALE: usize = 2;
+const OVERLAY_BACKGROUND: Color = Color::from_argb(0xC0, 0x10, 0x10, 0x10);
+
+const EVENTS_COLOR: Color = Color::from_rgb(0x40, 0x80, 0xFF);
+const UPDATE_COLOR: Color = Color::from_rgb(0x40, 0xD0, 0x40);
+const REDRAW_COLOR: Color = Color::from_rgb(0xFF, 0xA0, 0x20);
+const PRESENT_COLOR: Color = Color::from_rgb(0xC0, 0x40, 0xFF);
+
+impl FrameStats {
+    pub fn new(capacity: usize) -> Self {
+        Self { history: VecDeque::with_capacity(capacity), capacity: capacity.max(1) }
+    }
+
+    pub fn push(&mut self, timings: FrameTimings) {
+        if self.history.len() == self.capacity {
+            self.history.pop_front();
+        }
+
+        self.history.push_back(timings);
+    }
+
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn last(&self) -> Option<FrameTimings> {
+        self.history.back().copied()
+    }
+
+    // oldest first
+    pub fn history(&self) -> impl Iterator<Item = &FrameTimings> {
+        self.history.iter()
+    }
+
+    pub fn average(&self) -> FrameTimings {
+        if self.history.is_empty() {
+            return FrameTimings::default();
+        }
+
+        let sum = self.history.iter().fold(FrameTimings::default(), |acc, t| FrameTimings {
+            events: acc.events + t.events,
+            update: acc.update + t.update,
+            redraw: acc.redraw + t.redraw,
+            present: acc.present + t.present,
+            frame: acc.frame + t.frame
+        });
+
+        let len = self.history.len() as f64;
+
+        FrameTimings {
+            events: sum.events / len,
+            update: sum.update / len,
+            redraw: sum.redraw / len,
+            present: sum.present / len,
+            frame: sum.frame / len
+        }
+    }
+
+    // averaged over the history, 0 while there is none
+    pub fn fps(&self) -> f64 {
+        let frame = self.average().frame;
+
+        if frame > 0.0 { 1.0 / frame } else { 0.0 }
+    }
+
+    // Draws the FPS and a bar per frame in the top left corner, the parts of a bar are stacked
+    // events, update, redraw and present. The white line marks the target frame time
+    pub fn draw_overlay(&self, buffer: &mut Buffer, target_frame_time: f64) {
+        let Vec2 { x: left, y: top } = OVERLAY_POS;
+
+        // the busy time excludes the pacing sleep
+        let text = format!("{:.0} fps {:.1} ms", self.fps(), self.average().busy() * 1000.0);
+        let text_size = BitmapFont::builtin().measure(&text, OVERLAY_TEXT_SCALE);
+
+        let width = self.capacity as isize;
+        let graph_top = top + text_size.height as isize + 4;
+        let bottom = graph_top + OVERLAY_GRAPH_HEIGHT;
+        let right = left + width.max(text_size.width as isize);
+
+        buffer.fill_rectangle(
+            Vec2::new(Vec2::new(left - 4, top - 4), Vec2::new(right + 3, bottom + 3)),
+            OVERLAY_BACKGROUND
+        );
+
+        buffer.draw_text(&text, OVERLAY_POS, Color::WHITE, OVERLAY_TEXT_SCALE);
+
+        // twice the target frame time fits into the graph
+        let scale = OVERLAY_GRAPH_HEIGHT as f64 / (2.0 * target_frame_time);
+
+        for (index, timings) in self.history.iter().enumerate() {
+            let x = left + index as isize;
+            let mut y = bottom as f64;
+
+            for (time, color) in [
+                (timings.events, EVENTS_COLOR),
+                (timings.update, UPDATE_COLOR),
+                (timings.redraw, REDRAW_COLOR),
+                (timings.present, PRESENT_COLOR)
+            ] {
+                let next = (y - time * scale).max(graph_top as f64);
+
+                if next.round() < y.round() {
+                    buffer.draw_line(
+                        Vec2::new(x, y.round() as isize - 1),
+                        Vec2::new(x, next.round() as isize),
+                        color
+                    );
+                }
+
+                y = next;
+            }
+        }
+
+        let target = bottom - (target_frame_time * scale).round() as isize;
+
+        buffer.draw_line(Vec2::new(left, target), Vec2::new(left + width - 1, target), Color::WHITE);
+    }
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        Self::new(120)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render::stats::{FrameStats, FrameTimings};
+
+    fn frame(frame: f64) -> FrameTimings {
+        FrameTimings { events: frame / 8.0, update: frame / 4.0, redraw: frame / 2.0, present: 0.0, frame }
+    }
+
+    #[test]
+    fn history_capacity() {
+        let mut stats = FrameStats::new(3);
+
+        for index in 1..=5 {
+            stats.push(frame(index as f64));
+        }
+
+        let frames: Vec<f64> = stats.history().map(|timings| timings.frame).collect();
+
+        assert_eq!(frames, [3.0, 4.0, 5.0]);
+        assert_eq!(stats.last(), Some(frame(5.0)));
+
+        // at least one frame is kept
+        let mut stats = FrameStats::new(0);
+        stats.push(frame(1.0));
+        stats.push(frame(2.0));
+
+        assert_eq!(stats.capacity(), 1);
+        assert_eq!(stats.history().count(), 1);
+    }
+
+    #[test]
+    fn average_and_fps() {
+        let mut stats = FrameStats::new(4);
+
+        assert_eq!(stats.average(), FrameTimings::default());
+        assert_eq!(stats.fps(), 0.0);
+
+        stats.push(frame(0.01));
+        stats.push(frame(0.03));
+
+        assert_eq!(stats.average(), frame(0.02));
+        assert!((stats.fps() - 50.0).abs() < 1e-9);
+        assert!((stats.average().busy() - 0.0175).abs() < 1e-12);
+
+        stats.clear();
+        assert_eq!(stats.fps(), 0.0);
+    }
+}
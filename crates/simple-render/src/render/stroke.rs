@@ -0,0 +1,594 @@
+use std::f64::consts::TAU;
+
+use simple_linear_algebra::vector::{Vector, vec2::Vec2};
+
+use crate::{color::Color, render::{buffer::Buffer, raster::Rasterizer, shape::arc_points}};
+
+// how far the polygons of round caps and joins may be off the circle, in pixels
+const ROUND_TOLERANCE: f64 = 0.1;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineCap {
+    // the line ends exactly at the end point
+    #[default]
+    Butt,
+    // extended by half the width
+    Square,
+    Round
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineJoin {
+    // sharp corners, replaced by a bevel past the miter limit
+    #[default]
+    Miter,
+    Bevel,
+    Round
+}
+
+// How `draw_polyline` strokes. The dash pattern alternates drawn and skipped lengths in pixels,
+// starting `dash_offset` pixels into it
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineStyle {
+    width: f64,
+    cap: LineCap,
+    join: LineJoin,
+    // the longest miter in line widths
+    miter_limit: f64,
+    dash: Vec<f64>,
+    dash_offset: f64,
+    anti_aliased: bool
+}
+
+impl LineStyle {
+    pub const fn new(width: f64) -> Self {
+        Self {
+            width,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            miter_limit: 4.0,
+            dash: Vec::new(),
+            dash_offset: 0.0,
+            anti_aliased: true
+        }
+    }
+
+    pub const fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub const fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub const fn with_miter_limit(mut self, miter_limit: f64) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+
+    // an empty pattern or one without positive lengths draws a solid line
+    pub fn with_dash(mut self, pattern: &[f64], offset: f64) -> Self {
+        self.dash = pattern.to_vec();
+        self.dash_offset = offset;
+        self
+    }
+
+    pub const fn with_anti_aliasing(mut self, anti_aliased: bool) -> Self {
+        self.anti_aliased = anti_aliased;
+        self
+    }
+
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    pub fn cap(&self) -> LineCap {
+        self.cap
+    }
+
+    pub fn join(&self) -> LineJoin {
+        self.join
+    }
+
+    pub fn dash(&self) -> &[f64] {
+        &self.dash
+    }
+
+    pub fn is_anti_aliased(&self) -> bool {
+        self.anti_aliased
+    }
+
+    fn is_dashed(&self) -> bool {
+        let total: f64 = self.dash.iter().map(|len| len.max(0.0)).sum();
+
+        total > 0.0 && total.is_finite()
+    }
+
+    // splits the polyline into the parts the dash pattern draws
+    fn dashes(&self, points: &[Vec2<f64>]) -> Vec<Vec<Vec2<f64>>> {
+        if !self.is_dashed() {
+            return vec![points.to_vec()];
+        }
+
+        let total: f64 = self.dash.iter().map(|len| len.max(0.0)).sum();
+
+        // an odd pattern is repeated so drawn and skipped parts alternate
+        let pattern: Vec<f64> = match self.dash.len() % 2 {
+            0 => self.dash.iter().map(|len| len.max(0.0)).collect(),
+            _ => self.dash.iter().chain(&self.dash).map(|len| len.max(0.0)).collect()
+        };
+
+        let mut index = 0;
+        let mut left = pattern[0];
+        let mut skip = self.dash_offset.rem_euclid(total * (pattern.len() / self.dash.len()) as f64);
+
+        while skip > 0.0 {
+            if skip < left {
+                left -= skip;
+                break;
+            }
+
+            skip -= left;
+            index = (index + 1) % pattern.len();
+            left = pattern[index];
+        }
+
+        let mut dashes = Vec::new();
+        let mut current = vec![points[0]];
+
+        for segment in points.windows(2) {
+            let (start, end) = (segment[0], segment[1]);
+            let len = (end - start).length();
+
+            let mut done = 0.0;
+
+            while len - done > left {
+                done += left;
+
+                let point = start.lerp(end, done / len);
+
+                if index % 2 == 0 {
+                    current.push(point);
+                    dashes.push(std::mem::take(&mut current));
+                } else {
+                    current = vec![point];
+                }
+
+                index = (index + 1) % pattern.len();
+                left = pattern[index];
+            }
+
+            left -= len - done;
+
+            if index % 2 == 0 {
+                current.push(end);
+            }
+        }
+
+        if index % 2 == 0 && current.len() > 1 {
+            dashes.push(current);
+        }
+
+        dashes
+    }
+}
+
+impl Default for LineStyle {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+fn left_normal(direction: Vec2<f64>) -> Vec2<f64> {
+    Vec2::new(direction.y, -direction.x)
+}
+
+// the rasterizer needs every polygon in the same direction, otherwise overlaps cancel out
+fn add_polygon(rasterizer: &mut Rasterizer, points: &[Vec2<f64>], origin: Vec2<f64>) {
+    let area: f64 = points.iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum();
+
+    let mut add = |start: Vec2<f64>, end: Vec2<f64>| rasterizer.line(start - origin, end - origin);
+
+    for (start, end) in points.iter().zip(points.iter().cycle().skip(1)) {
+        if area >= 0.0 {
+            add(*start, *end);
+        } else {
+            add(*end, *start);
+        }
+    }
+}
+
+fn circle(center: Vec2<f64>, radius: f64) -> Vec<Vec2<f64>> {
+    let mut points = arc_points(center, Vec2::splat(radius), 0.0, TAU, ROUND_TOLERANCE);
+    points.pop();
+
+    points
+}
+
+// Outline polygons of one dash: a quad per segment, the joins between them and the caps at both ends.
+// A closed outline also joins its last segment to the first one and has no caps
+// the part of the segment inside [min, max] along x, the ends may come back swapped
+fn clip_axis(start: Vec2<f64>, end: Vec2<f64>, min: f64, max: f64) -> Option<(Vec2<f64>, Vec2<f64>)> {
+    let (start, end) = if start.x <= end.x { (start, end) } else { (end, start) };
+
+    if end.x < min || start.x > max {
+        return None;
+    }
+
+    // halved so the difference of far off points can't overflow
+    let slope = (end.y * 0.5 - start.y * 0.5) / (end.x * 0.5 - start.x * 0.5);
+
+    // measured from the closer end, a far off one would swamp the result
+    let at = |x: f64| {
+        let base = if (x - start.x).abs() < (x - end.x).abs() { start } else { end };
+        Vec2::new(x, base.y + (x - base.x) * slope)
+    };
+
+    let clipped_start = if start.x < min { at(min) } else { start };
+    let clipped_end = if end.x > max { at(max) } else { end };
+
+    Some((clipped_start, clipped_end))
+}
+
+// the part of the segment inside [min, max], None when it misses
+fn clip_segment(start: Vec2<f64>, end: Vec2<f64>, min: Vec2<f64>, max: Vec2<f64>) -> Option<(Vec2<f64>, Vec2<f64>)> {
+    let swap = |point: Vec2<f64>| Vec2::new(point.y, point.x);
+
+    let (start, end) = clip_axis(start, end, min.x, max.x)?;
+    let (start, end) = clip_axis(swap(start), swap(end), min.y, max.y)?;
+
+    let finite = |point: Vec2<f64>| point.x.is_finite() && point.y.is_finite();
+
+    (finite(start) && finite(end)).then(|| (swap(start), swap(end)))
+}
+
+fn stroke_polygons(points: &[Vec2<f64>], closed: bool, style: &LineStyle) -> Vec<Vec<Vec2<f64>>> {
+    let half = style.width / 2.0;
+
+    let mut points = points.to_vec();
+    points.dedup();
+
+    if closed && points.len() > 2 && points.first() == points.last() {
+        points.pop();
+    }
+
+    let closed = closed && points.len() > 2;
+
+    if closed {
+        points.push(points[0]);
+    }
+
+    let mut polygons = Vec::new();
+
+    if points.len() == 1 {
+        // a zero length line only shows with caps that stick out
+        match style.cap {
+            LineCap::Butt => (),
+            LineCap::Square => {
+                let p = points[0];
+                polygons.push(vec![
+                    p + Vec2::new(-half, -half),
+                    p + Vec2::new(half, -half),
+                    p + Vec2::new(half, half),
+                    p + Vec2::new(-half, half)
+                ]);
+            },
+            LineCap::Round => polygons.push(circle(points[0], half))
+        }
+
+        return polygons;
+    }
+
+    let directions: Vec<Vec2<f64>> = points.windows(2)
+        .map(|segment| (segment[1] - segment[0]).to_normalized())
+        .collect();
+
+    let last = directions.len() - 1;
+
+    for (index, (segment, &direction)) in points.windows(2).zip(&directions).enumerate() {
+        let mut start = segment[0];
+        let mut end = segment[1];
+
+        let normal = left_normal(direction) * half;
+
+        if style.cap == LineCap::Square && !closed {
+            if index == 0 {
+                start -= direction * half;
+            }
+
+            if index == last {
+                end += direction * half;
+            }
+        }
+
+        polygons.push(vec![start + normal, end + normal, end - normal, start - normal]);
+    }
+
+    // the closing join is between the last and the first direction
+    let wrap = closed.then(|| [directions[directions.len() - 1], directions[0]]);
+
+    for (index, pair) in directions.windows(2).chain(wrap.as_ref().map(|pair| pair.as_slice())).enumerate() {
+        let (before, after) = (pair[0], pair[1]);
+        let vertex = points[(index + 1) % (points.len() - 1)];
+
+        let turn = before.cross(after);
+
+        if turn == 0.0 && before.dot(after) > 0.0 {
+            continue;
+        }
+
+        // the join fills the gap on the outer side of the turn
+        let side = if turn > 0.0 { -1.0 } else { 1.0 };
+
+        let outer_before = vertex + left_normal(before) * (half * side);
+        let outer_after = vertex + left_normal(after) * (half * side);
+
+        match style.join {
+            LineJoin::Round => polygons.push(circle(vertex, half)),
+            LineJoin::Bevel => polygons.push(vec![vertex, outer_before, outer_after]),
+            LineJoin::Miter => {
+                let bisector = (left_normal(before) + left_normal(after)).to_normalized() * side;
+                let cos = bisector.dot(left_normal(before) * side);
+
+                // 1 / cos is the miter length in line widths
+                if cos > 0.0 && 1.0 / cos <= style.miter_limit {
+                    let miter = vertex + bisector * (half / cos);
+                    polygons.push(vec![vertex, outer_before, miter, outer_after]);
+                } else {
+                    polygons.push(vec![vertex, outer_before, outer_after]);
+                }
+            }
+        }
+    }
+
+    if style.cap == LineCap::Round && !closed {
+        polygons.push(circle(points[0], half));
+        polygons.push(circle(points[points.len() - 1], half));
+    }
+
+    polygons
+}
+
+impl Buffer {
+    // Xiaolin Wu's line, one pixel wide and blended by coverage. Pixel (x, y) covers [x, x + 1) x [y, y + 1)
+    pub fn draw_line_aa(&mut self, start: Vec2<f64>, end: Vec2<f64>, color: Color) {
+        if !(start.x.is_finite() && start.y.is_finite() && end.x.is_finite() && end.y.is_finite()) {
+            return;
+        }
+
+        // clipped a little outside the buffer so the end pixels that are drawn keep their coverage
+        let margin = Vec2::splat(2.0);
+        let size = Vec2::new(self.size.width as f64, self.size.height as f64);
+
+        let Some((start, end)) = clip_segment(start, end, -margin, size + margin) else {
+            return;
+        };
+
+        // moved so pixel centers are on whole numbers
+        let (mut start, mut end) = (start - Vec2::splat(0.5), end - Vec2::splat(0.5));
+
+        let steep = (end.y - start.y).abs() > (end.x - start.x).abs();
+
+        if steep {
+            start = Vec2::new(start.y, start.x);
+            end = Vec2::new(end.y, end.x);
+        }
+
+        if start.x > end.x {
+            (start, end) = (end, start);
+        }
+
+        // only the part inside the buffer is walked
+        let limit = if steep { self.size.height } else { self.size.width } as isize;
+
+        let mut plot = |x: isize, y: isize, coverage: f64| {
+            let point = if steep { Vec2::new(y, x) } else { Vec2::new(x, y) };
+            let coverage = (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+            if coverage != 0 {
+                self.draw_point(point, color.fade(coverage));
+            }
+        };
+
+        let dx = end.x - start.x;
+        let gradient = if dx == 0.0 { 1.0 } else { (end.y - start.y) / dx };
+
+        let fraction = |value: f64| value - value.floor();
+
+        // the end points get the part of their pixel the line covers
+        let mut endpoint = |point: Vec2<f64>, gap: f64| -> isize {
+            let x = point.x.round();
+            let y = point.y + gradient * (x - point.x);
+
+            plot(x as isize, y.floor() as isize, (1.0 - fraction(y)) * gap);
+            plot(x as isize, y.floor() as isize + 1, fraction(y) * gap);
+
+            x as isize
+        };
+
+        let first = endpoint(start, 1.0 - fraction(start.x + 0.5));
+        let last = endpoint(end, fraction(end.x + 0.5));
+
+        let from = (first + 1).max(0);
+        let to = last.min(limit);
+
+        let mut y = start.y + gradient * (start.x.round() - start.x) + gradient * (from - first) as f64;
+
+        for x in from..to {
+            plot(x, y.floor() as isize, 1.0 - fraction(y));
+            plot(x, y.floor() as isize + 1, fraction(y));
+
+            y += gradient;
+        }
+    }
+
+    // single thin segments with butt caps go through `draw_line_aa`, the rest is outlined and filled with coverage
+    pub fn draw_polyline(&mut self, points: &[Vec2<f64>], style: &LineStyle, color: Color) {
+        self.stroke(points, false, style, color);
+    }
+
+    // the outline of a polygon, the last point is connected back to the first one
+    pub fn draw_polygon(&mut self, points: &[Vec2<f64>], style: &LineStyle, color: Color) {
+        self.stroke(points, true, style, color);
+    }
+
+    pub fn draw_styled_line(&mut self, start: Vec2<f64>, end: Vec2<f64>, style: &LineStyle, color: Color) {
+        self.draw_polyline(&[start, end], style, color);
+    }
+
+    fn stroke(&mut self, points: &[Vec2<f64>], closed: bool, style: &LineStyle, color: Color) {
+        let mut points: Vec<Vec2<f64>> = points.iter()
+            .copied()
+            .filter(|point| point.x.is_finite() && point.y.is_finite())
+            .collect();
+
+        if points.is_empty() || style.width <= 0.0 {
+            return;
+        }
+
+        let thin = style.width <= 1.0 && style.cap == LineCap::Butt;
+
+        // dashes and aliased thin lines run over the closing segment like over any other
+        let closed = if closed && ((thin && !style.anti_aliased) || style.is_dashed()) {
+            points.push(points[0]);
+            false
+        } else {
+            closed
+        };
+
+        // Wu's lines are only drawn where no two of them meet, a shared point would be blended twice.
+        // Their coverage is scaled by the width, like the outline of a thinner line would be
+        let (lines, dashes): (Vec<_>, Vec<_>) = style.dashes(&points)
+            .into_iter()
+            .partition(|dash| thin && (!style.anti_aliased || (dash.len() == 2 && !closed)));
+
+        for line in &lines {
+            for segment in line.windows(2) {
+                if style.anti_aliased {
+                    self.draw_line_aa(segment[0], segment[1], color.fade((style.width * 255.0).round() as u8));
+                } else {
+                    let to_pixel = |point: Vec2<f64>| Vec2::new(point.x.floor() as isize, point.y.floor() as isize);
+
+                    self.draw_line(to_pixel(segment[0]), to_pixel(segment[1]), color);
+                }
+            }
+        }
+
+        let polygons: Vec<Vec<Vec2<f64>>> = dashes.iter()
+            .flat_map(|dash| stroke_polygons(dash, closed, style))
+            .collect();
+
+        // only the part of the buffer the stroke touches is rasterised
+        let (mut min, mut max) = (Vec2::splat(f64::INFINITY), Vec2::splat(f64::NEG_INFINITY));
+
+        for point in polygons.iter().flatten() {
+            min = Vec2::new(min.x.min(point.x), min.y.min(point.y));
+            max = Vec2::new(max.x.max(point.x), max.y.max(point.y));
+        }
+
+        let min = Vec2::new(min.x.floor().max(0.0), min.y.floor().max(0.0));
+        let max = Vec2::new(
+            max.x.ceil().min(self.size.width as f64),
+            max.y.ceil().min(self.size.height as f64)
+        );
+
+        if min.x >= max.x || min.y >= max.y {
+            return;
+        }
+
+        let (width, height) = ((max.x - min.x) as usize, (max.y - min.y) as usize);
+
+        let mut rasterizer = Rasterizer::new(width, height);
+
+        for polygon in &polygons {
+            add_polygon(&mut rasterizer, polygon, min);
+        }
+
+        let origin = Vec2::new(min.x as isize, min.y as isize);
+
+        for (index, coverage) in rasterizer.coverage().into_iter().enumerate() {
+            let coverage = match style.anti_aliased {
+                true => coverage,
+                false if coverage >= 128 => u8::MAX,
+                false => 0
+            };
+
+            if coverage != 0 {
+                let point = origin + Vec2::new((index % width) as isize, (index / width) as isize);
+
+                self.raw_draw_point(point, color.fade(coverage));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simple_linear_algebra::vector::vec2::Vec2;
+
+    use crate::{color::Color, render::{buffer::{Buffer, BufferSize}, stroke::LineStyle}};
+
+    fn stroked(points: &[Vec2<f64>], width: f64, color: Color) -> Buffer {
+        let mut buffer = Buffer::init(BufferSize::new(12, 12));
+
+        buffer.fill(Color::BLACK);
+        buffer.draw_polyline(points, &LineStyle::new(width), color);
+
+        buffer
+    }
+
+    #[test]
+    fn thin_width() {
+        let line = [Vec2::new(2.0, 5.5), Vec2::new(10.0, 5.5)];
+
+        assert_eq!(stroked(&line, 1.0, Color::WHITE).get_point(Vec2::new(5, 5)), Color::WHITE.0);
+
+        let (red, _, _) = Color::from_argb_u32(stroked(&line, 0.5, Color::WHITE).get_point(Vec2::new(5, 5))).to_rgb();
+        assert!((126..=129).contains(&red), "{red}");
+    }
+
+    #[test]
+    fn shared_points_blended_once() {
+        let corner = [Vec2::new(2.5, 2.5), Vec2::new(8.5, 2.5), Vec2::new(8.5, 8.5)];
+
+        let buffer = stroked(&corner, 1.0, Color::WHITE.fade(128));
+
+        let straight = buffer.get_point(Vec2::new(5, 2));
+
+        assert_ne!(straight, Color::BLACK.0);
+        assert_eq!(buffer.get_point(Vec2::new(8, 2)), straight);
+        assert_eq!(buffer.get_point(Vec2::new(8, 5)), straight);
+    }
+
+    #[test]
+    fn far_off_endpoints() {
+        let mut buffer = Buffer::init(BufferSize::new(8, 4));
+        buffer.fill(Color::BLACK);
+
+        buffer.draw_line_aa(Vec2::new(-1e300, 0.5), Vec2::new(5.0, 0.5), Color::RED);
+
+        for x in 0..5 {
+            assert_eq!(buffer.get_point(Vec2::new(x, 0)), Color::RED.0, "{x}");
+        }
+
+        for x in 5..8 {
+            assert_eq!(buffer.get_point(Vec2::new(x, 0)), Color::BLACK.0, "{x}");
+        }
+
+        buffer.draw_line_aa(Vec2::new(f64::MIN, f64::MIN), Vec2::new(f64::MAX, f64::MAX), Color::RED);
+        buffer.draw_line_aa(Vec2::new(-1e300, 1e300), Vec2::new(1e300, -1e300), Color::RED);
+
+        // off the buffer entirely
+        let mut untouched = Buffer::init(BufferSize::new(8, 4));
+        untouched.fill(Color::BLACK);
+        untouched.draw_line_aa(Vec2::new(-1e300, -10.0), Vec2::new(1e300, -10.0), Color::RED);
+        untouched.draw_line_aa(Vec2::new(20.0, -1e300), Vec2::new(20.0, 1e300), Color::RED);
+
+        assert!(untouched.raw_buffer.0.iter().all(|&pixel| pixel == Color::BLACK.0));
+    }
+}